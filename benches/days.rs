@@ -0,0 +1,41 @@
+use aoc_2022::bench::{bench_day, table};
+use aoc_2022::inputs;
+use aoc_2022::DAYS;
+use std::env;
+use std::process::ExitCode;
+use std::time::Duration;
+
+// `cargo bench [-- DAY...]` times parsing and both parts of each day against its default puzzle input
+fn main() -> ExitCode {
+    let filter: Vec<String> = env::args()
+        .skip(1)
+        .filter(|a| !a.starts_with("--"))
+        .collect();
+    let budget = Duration::from_millis(200);
+
+    let mut results = Vec::new();
+    for day in DAYS {
+        if !filter.is_empty() && !filter.iter().any(|f| f == day.name) {
+            continue;
+        }
+
+        let text = match inputs::default(day.name).and_then(|f| f.input().text()) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("{}: {e}", day.name);
+                return ExitCode::FAILURE;
+            }
+        };
+
+        match bench_day(day, &text, budget) {
+            Ok(r) => results.push(r),
+            Err(e) => {
+                eprintln!("{}: {e}", day.name);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    print!("{}", table(&results));
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,64 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+// With the `embedded-inputs` feature, writes `$OUT_DIR/inputs.rs`: a table of every
+// `src/decNN/{examples,inputs}/*.txt` and the `answers.txt` manifest, keyed by its path relative to
+// the crate root.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var_os("CARGO_FEATURE_EMBEDDED_INPUTS").is_none() {
+        return;
+    }
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let src = root.join("src");
+    println!("cargo:rerun-if-changed={}", src.display());
+    let answers = root.join("answers.txt");
+    println!("cargo:rerun-if-changed={}", answers.display());
+
+    let mut files = Vec::new();
+    for dir in fs::read_dir(&src).unwrap() {
+        let dir = dir.unwrap().path();
+        let is_day = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("dec"));
+        if !is_day || !dir.is_dir() {
+            continue;
+        }
+
+        for set in ["examples", "inputs"] {
+            let Ok(entries) = fs::read_dir(dir.join(set)) else {
+                continue;
+            };
+            for file in entries {
+                let file = file.unwrap().path();
+                if file.extension().is_some_and(|e| e == "txt") {
+                    files.push(file);
+                }
+            }
+        }
+    }
+    files.sort();
+    if answers.is_file() {
+        files.push(answers);
+    }
+
+    let mut out = String::from("pub static INPUTS: &[(&str, &[u8])] = &[\n");
+    for file in files {
+        let key = file.strip_prefix(&root).unwrap().to_str().unwrap();
+        writeln!(
+            out,
+            "    ({key:?}, include_bytes!({:?})),",
+            file.display().to_string()
+        )
+        .unwrap();
+    }
+    out.push_str("];\n");
+
+    let dest = PathBuf::from(env::var("OUT_DIR").unwrap()).join("inputs.rs");
+    fs::write(dest, out).unwrap();
+}
@@ -1,38 +0,0 @@
-use crate::utils::*;
-
-fn solve_1(input: &str) -> usize {
-    unimplemented!()
-}
-
-fn solve_2(input: &str) -> usize {
-    unimplemented!()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn example_1() {
-        let input = "src/__DAY__/example_1.txt";
-        assert_eq!(0, solve_1(input));
-    }
-
-    #[test]
-    fn puzzle_1() {
-        let input = "src/__DAY__/input_1.txt";
-        assert_eq!(0, solve_1(input));
-    }
-
-    #[test]
-    fn example_2() {
-        let input = "src/__DAY__/example_1.txt";
-        assert_eq!(0, solve_2(input));
-    }
-
-    #[test]
-    fn puzzle_2() {
-        let input = "src/__DAY__/input_1.txt";
-        assert_eq!(0, solve_2(input));
-    }
-}
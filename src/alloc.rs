@@ -0,0 +1,155 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct AllocStats {
+    // allocations and reallocations
+    pub allocs: u64,
+    pub bytes: u64,
+    // most bytes live at once, over what was already live when measuring began
+    pub peak: u64,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} total, {} peak",
+            self.allocs,
+            fmt_bytes(self.bytes),
+            fmt_bytes(self.peak)
+        )
+    }
+}
+
+pub fn fmt_bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = n as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{n} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+// Counts the allocations `f` makes on the current thread. Always `None` unless the crate is built
+// with the `alloc-stats` feature, which installs the counting global allocator.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    #[cfg(feature = "alloc-stats")]
+    {
+        let (out, stats) = counting::measure(f);
+        (out, Some(stats))
+    }
+
+    #[cfg(not(feature = "alloc-stats"))]
+    {
+        (f(), None)
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use super::AllocStats;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    // Counters are per thread so jobs on the parallel runner don't see each other's allocations.
+    // Memory freed on a different thread than it was allocated on can drive `LIVE` negative.
+    thread_local! {
+        static ALLOCS: Cell<u64> = const { Cell::new(0) };
+        static BYTES: Cell<u64> = const { Cell::new(0) };
+        static LIVE: Cell<i64> = const { Cell::new(0) };
+        static PEAK: Cell<i64> = const { Cell::new(0) };
+    }
+
+    pub struct Counting;
+
+    #[global_allocator]
+    static GLOBAL: Counting = Counting;
+
+    fn record_alloc(size: usize) {
+        // `try_with` since thread-locals are unavailable while a thread is being torn down
+        let _ = ALLOCS.try_with(|a| a.set(a.get() + 1));
+        let _ = BYTES.try_with(|b| b.set(b.get() + size as u64));
+        let _ = LIVE.try_with(|l| {
+            let live = l.get() + size as i64;
+            l.set(live);
+            let _ = PEAK.try_with(|p| p.set(p.get().max(live)));
+        });
+    }
+
+    fn record_free(size: usize) {
+        let _ = LIVE.try_with(|l| l.set(l.get() - size as i64));
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            record_alloc(layout.size());
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            record_alloc(layout.size());
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            record_free(layout.size());
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            record_free(layout.size());
+            record_alloc(new_size);
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+        let (allocs, bytes, live) = (ALLOCS.get(), BYTES.get(), LIVE.get());
+        let outer_peak = PEAK.replace(live);
+
+        let out = f();
+
+        let peak = PEAK.get();
+        PEAK.set(outer_peak.max(peak));
+        let stats = AllocStats {
+            allocs: ALLOCS.get() - allocs,
+            bytes: BYTES.get() - bytes,
+            peak: (peak - live).max(0) as u64,
+        };
+        (out, stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        assert_eq!("512 B", fmt_bytes(512));
+        assert_eq!("1.5 KiB", fmt_bytes(1536));
+        assert_eq!("3.0 MiB", fmt_bytes(3 << 20));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc-stats")]
+    fn counts_allocations() {
+        let (_, stats) = measure(|| {
+            let a = vec![0u8; 1000];
+            drop(a);
+            let b = vec![0u8; 400];
+            b.len()
+        });
+        let stats = stats.unwrap();
+        assert_eq!(2, stats.allocs);
+        assert_eq!(1400, stats.bytes);
+        assert_eq!(1000, stats.peak);
+    }
+}
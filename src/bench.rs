@@ -0,0 +1,167 @@
+use crate::alloc::{self, fmt_bytes, AllocStats};
+use crate::error::Result;
+use crate::solution::{Day, Part};
+use crate::utils::Input;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone)]
+pub struct Timing {
+    pub runs: u32,
+    pub mean: Duration,
+    pub min: Duration,
+}
+
+impl Timing {
+    // repeats `f` until `budget` is spent, always running it at least once
+    pub fn measure<F: FnMut()>(budget: Duration, mut f: F) -> Self {
+        let mut runs = 0;
+        let mut total = Duration::ZERO;
+        let mut min = Duration::MAX;
+
+        while runs == 0 || total < budget {
+            let start = Instant::now();
+            f();
+            let elapsed = start.elapsed();
+
+            runs += 1;
+            total += elapsed;
+            min = min.min(elapsed);
+        }
+
+        Self {
+            runs,
+            mean: total / runs,
+            min,
+        }
+    }
+}
+
+pub struct DayBench {
+    pub day: &'static str,
+    pub parse: Timing,
+    pub parts: [Timing; 2],
+    // allocations of a single parse and of each part, with the `alloc-stats` feature
+    pub parse_alloc: Option<AllocStats>,
+    pub parts_alloc: [Option<AllocStats>; 2],
+    // every non-default variant, timed like the parts
+    pub variants: Vec<(Part, &'static str, Timing)>,
+}
+
+// parsing is timed from an in-memory copy of the input, so I/O stays out of the numbers
+pub fn bench_day(day: &Day, text: &str, budget: Duration) -> Result<DayBench> {
+    let (parsed, parse_alloc) = alloc::measure(|| day.parse(Input::Str(text)));
+    let parsed = parsed?;
+    let parts_alloc = Part::ALL.map(|part| alloc::measure(|| day.solve(parsed.as_ref(), part)).1);
+
+    let parse = Timing::measure(budget, || {
+        black_box(day.parse(Input::Str(black_box(text))).ok());
+    });
+
+    let parts = Part::ALL.map(|part| {
+        Timing::measure(budget, || {
+            black_box(day.solve(black_box(parsed.as_ref()), part).ok());
+        })
+    });
+
+    let variants = Part::ALL
+        .into_iter()
+        .flat_map(|part| {
+            day.variants(part)
+                .into_iter()
+                .skip(1)
+                .map(move |v| (part, v))
+        })
+        .map(|(part, variant)| {
+            let timing = Timing::measure(budget, || {
+                black_box(day.solve_variant(black_box(parsed.as_ref()), part, variant));
+            });
+            (part, variant, timing)
+        })
+        .collect();
+
+    Ok(DayBench {
+        day: day.name,
+        parse,
+        parts,
+        parse_alloc,
+        parts_alloc,
+        variants,
+    })
+}
+
+pub fn table(results: &[DayBench]) -> String {
+    let mut out = format!(
+        "{:<8}{:>14}{:>14}{:>14}{:>14}\n",
+        "day", "parse", "part 1", "part 2", "total"
+    );
+
+    for r in results {
+        let total = r.parse.mean + r.parts[0].mean + r.parts[1].mean;
+        out += &format!(
+            "{:<8}{:>14}{:>14}{:>14}{:>14}\n",
+            r.day,
+            format!("{:.2?}", r.parse.mean),
+            format!("{:.2?}", r.parts[0].mean),
+            format!("{:.2?}", r.parts[1].mean),
+            format!("{total:.2?}"),
+        );
+    }
+
+    if results.iter().any(|r| !r.variants.is_empty()) {
+        out += &format!(
+            "\n{:<8}{:<6}{:<10}{:>14}{:>14}\n",
+            "day", "part", "variant", "mean", "vs default"
+        );
+        for r in results {
+            for &(part, variant, timing) in &r.variants {
+                let default = r.parts[if part == Part::One { 0 } else { 1 }].mean;
+                let ratio = timing.mean.as_secs_f64() / default.as_secs_f64();
+                out += &format!(
+                    "{:<8}{:<6}{:<10}{:>14}{:>14}\n",
+                    r.day,
+                    part,
+                    variant,
+                    format!("{:.2?}", timing.mean),
+                    format!("{ratio:.2}x"),
+                );
+            }
+        }
+    }
+
+    if results.iter().any(|r| r.parse_alloc.is_some()) {
+        out += &format!(
+            "\n{:<8}{:>24}{:>24}{:>24}\n",
+            "day", "parse allocs/peak", "part 1 allocs/peak", "part 2 allocs/peak"
+        );
+        for r in results {
+            let cell = |a: Option<AllocStats>| {
+                let a = a.unwrap_or_default();
+                format!("{} / {}", a.allocs, fmt_bytes(a.peak))
+            };
+            out += &format!(
+                "{:<8}{:>24}{:>24}{:>24}\n",
+                r.day,
+                cell(r.parse_alloc),
+                cell(r.parts_alloc[0]),
+                cell(r.parts_alloc[1]),
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_runs_at_least_once() {
+        let mut ct = 0;
+        let t = Timing::measure(Duration::ZERO, || ct += 1);
+        assert_eq!(1, ct);
+        assert_eq!(1, t.runs);
+        assert_eq!(t.min, t.mean);
+    }
+}
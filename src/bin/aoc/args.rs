@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+// Minimal argument parser: subcommands pull out the options they understand, then their
+// positional arguments, then `finish` rejects anything left over. Options come first because a
+// positional can't tell the value of `--name value` from an argument.
+pub struct Args {
+    args: VecDeque<String>,
+}
+
+impl Args {
+    pub fn new<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self {
+            args: iter.into_iter().collect(),
+        }
+    }
+
+    pub fn positional(&mut self) -> Option<String> {
+        let idx = self.args.iter().position(|a| !a.starts_with("--"))?;
+        self.args.remove(idx)
+    }
+
+    pub fn opt(&mut self, name: &str) -> Result<Option<String>, String> {
+        let flag = format!("--{name}");
+        let prefix = format!("--{name}=");
+
+        let Some(idx) = self
+            .args
+            .iter()
+            .position(|a| *a == flag || a.starts_with(&prefix))
+        else {
+            return Ok(None);
+        };
+
+        let arg = self.args.remove(idx).unwrap();
+        if let Some(val) = arg.strip_prefix(&prefix) {
+            return Ok(Some(val.to_owned()));
+        }
+
+        self.args
+            .remove(idx)
+            .map(Some)
+            .ok_or_else(|| format!("missing value for {flag}"))
+    }
+
+    // whether the option `name` was given, taking no value
+    pub fn flag(&mut self, name: &str) -> bool {
+        let flag = format!("--{name}");
+        match self.args.iter().position(|a| *a == flag) {
+            Some(idx) => self.args.remove(idx).is_some(),
+            None => false,
+        }
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        match self.args.front() {
+            None => Ok(()),
+            Some(arg) => Err(format!("unexpected argument: {arg}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Args {
+        Args::new(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn options() {
+        let mut a = args("dec12 --part 2 --input=-");
+        assert_eq!(Some("2".to_owned()), a.opt("part").unwrap());
+        assert_eq!(Some("-".to_owned()), a.opt("input").unwrap());
+        assert_eq!(None, a.opt("input").unwrap());
+        assert_eq!(Some("dec12".to_owned()), a.positional());
+        assert!(a.finish().is_ok());
+    }
+
+    #[test]
+    fn options_before_day() {
+        let mut a = args("--part 2 dec12 --input -");
+        assert_eq!(Some("2".to_owned()), a.opt("part").unwrap());
+        assert_eq!(Some("-".to_owned()), a.opt("input").unwrap());
+        assert_eq!(Some("dec12".to_owned()), a.positional());
+        assert!(a.finish().is_ok());
+    }
+
+    #[test]
+    fn flags() {
+        let mut a = args("--explain dec12");
+        assert!(a.flag("explain"));
+        assert!(!a.flag("explain"));
+        assert_eq!(Some("dec12".to_owned()), a.positional());
+    }
+
+    #[test]
+    fn missing_value() {
+        let mut a = args("dec12 --part");
+        assert!(a.opt("part").is_err());
+    }
+
+    #[test]
+    fn leftovers() {
+        let mut a = args("dec12 --verbose");
+        assert_eq!(Some("dec12".to_owned()), a.positional());
+        assert!(a.finish().is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use crate::utils::*;
+use crate::{Result, Solution};
+
+pub struct __TYPE__;
+
+impl Solution for __TYPE__ {
+    type Input = Vec<String>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        Ok(input.lines()?.map(Line::into_string).collect())
+    }
+
+    fn part_1(_input: &Self::Input) -> Self::Output1 {
+        todo!()
+    }
+
+    fn part_2(_input: &Self::Input) -> Self::Output2 {
+        todo!()
+    }
+}
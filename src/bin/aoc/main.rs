@@ -0,0 +1,460 @@
+use aoc_2022::fuzz;
+use aoc_2022::gen::{self, GENERATORS};
+use aoc_2022::inputs::{self, InputFile};
+use aoc_2022::ledger::{Attempt, Ledger, Verdict};
+use aoc_2022::manifest::Manifest;
+use aoc_2022::params::Params;
+use aoc_2022::report::{self, Record};
+use aoc_2022::runner::{self, jobs, run_all};
+use aoc_2022::trace;
+use aoc_2022::{find_day, Input, Part, DAYS, DEFAULT_VARIANT};
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+mod args;
+mod new;
+mod watch;
+
+use args::Args;
+
+const USAGE: &str = "\
+usage: aoc <command> [options]
+
+commands:
+    attempt <day> <part> <answer> <verdict> [--input IN]
+                                            record a hand-submitted answer; <verdict> is
+                                            too-high, too-low, wrong or correct
+    all [--threads N] [--inputs SET] [--format F] [--output PATH]
+                                            solve every day against every input in parallel and
+                                            summarize; SET is all (default), examples or
+                                            puzzles, F is table (default), json or junit
+    fuzz [<day>...] [--runs N] [--seed S]   feed mutated examples to the parsers, saving any input
+                                            that panics to src/<day>/crashes
+    gen [<day> [--seed S] [--size NAME=N]... [--output PATH]]
+                                            generate an input of any size for stress testing;
+                                            without a day, list the sizes each day takes
+    list                                    list the registered days, their inputs and params
+    new <day>                               scaffold and register a new day, e.g. dec15
+    run <day> [--part 1|2] [--input IN] [--variant NAME] [--param NAME=VALUE]...
+        [--trace PATH] [--explain]          solve a day, optionally with its params overridden,
+                                            writing the events its simulation emits to PATH, or
+                                            showing how the day reached each answer
+    trace-diff <a> <b> [--context N]        compare two traces and show where they diverge
+    watch <day> [--part 1|2] [--input IN] [--param NAME=VALUE]...
+                                            re-run a day whenever its code or input changes
+
+IN is an input name like examples/1 or a user like alice, a path, or - for stdin. It defaults
+to the puzzle input of $AOC_USER, or the day's first puzzle input if that isn't set.
+
+Answers to named inputs are checked against the attempts recorded for them, with a warning
+when one repeats a rejected answer or falls outside the too-high/too-low bounds.
+";
+
+fn main() -> ExitCode {
+    let mut args = Args::new(env::args().skip(1));
+
+    let result = match args.positional().as_deref() {
+        Some("all") => all(args),
+        Some("attempt") => attempt(args),
+        Some("fuzz") => fuzz(args),
+        Some("gen") => generate(args),
+        Some("list") => list(args),
+        Some("new") => new::new(args),
+        Some("run") => run(args),
+        Some("trace-diff") => trace_diff(args),
+        Some("watch") => watch::watch(args),
+        Some(cmd) => Err(format!("unknown command: {cmd}\n\n{USAGE}")),
+        None => Err(USAGE.to_owned()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn all(mut args: Args) -> Result<(), String> {
+    let threads = match args.opt("threads")? {
+        Some(n) => n.parse().map_err(|e| format!("--threads: {e}"))?,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let format = args.opt("format")?.unwrap_or_else(|| "table".to_owned());
+    if !["table", "json", "junit"].contains(&format.as_str()) {
+        return Err(format!(
+            "--format: expected table, json or junit, found {format:?}"
+        ));
+    }
+    let set = args.opt("inputs")?.unwrap_or_else(|| "all".to_owned());
+    let keep: fn(&InputFile) -> bool = match set.as_str() {
+        "all" => |_| true,
+        "examples" => InputFile::is_example,
+        "puzzles" => |f| !f.is_example(),
+        _ => {
+            return Err(format!(
+                "--inputs: expected all, examples or puzzles, found {set:?}"
+            ))
+        }
+    };
+    let output = args.opt("output")?;
+    args.finish()?;
+
+    let mut names: HashMap<&str, Vec<String>> = HashMap::new();
+    for day in DAYS {
+        let files = inputs::list(day.name).map_err(|e| e.to_string())?;
+        let files = files.into_iter().filter(keep).map(|f| f.name);
+        names.insert(day.name, files.collect());
+    }
+    let jobs = jobs(DAYS, |d| names[d.name].clone());
+
+    let results = run_all(&jobs, threads, |d, name| {
+        InputFile::new(d.name, name).input()
+    });
+
+    let manifest = Manifest::load().map_err(|e| e.to_string())?;
+    let records: Vec<Record> = results.iter().map(|r| Record::new(r, &manifest)).collect();
+
+    let ledger = Ledger::load().map_err(|e| e.to_string())?;
+    for r in &records {
+        if let Some(answer) = &r.answer {
+            warn(&ledger, r.day, r.part, &r.input, answer);
+        }
+    }
+
+    let out = match format.as_str() {
+        "json" => report::json(&records),
+        "junit" => report::junit(&records),
+        _ => runner::table(&results),
+    };
+    match output {
+        Some(path) => fs::write(&path, out).map_err(|e| format!("{path}: {e}"))?,
+        None => print!("{out}"),
+    }
+
+    if records.iter().all(Record::is_ok) {
+        Ok(())
+    } else {
+        Err("some days failed".to_owned())
+    }
+}
+
+fn fuzz(mut args: Args) -> Result<(), String> {
+    let runs = match args.opt("runs")? {
+        Some(n) => n.parse().map_err(|e| format!("--runs: {e}"))?,
+        None => 10_000,
+    };
+    let seed = match args.opt("seed")? {
+        Some(s) => s.parse().map_err(|e| format!("--seed: {e}"))?,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64),
+    };
+    let mut days = Vec::new();
+    while let Some(name) = args.positional() {
+        days.push(find_day(&name).ok_or_else(|| format!("unknown day: {name}"))?);
+    }
+    if days.is_empty() {
+        days = DAYS.iter().collect();
+    }
+    args.finish()?;
+
+    println!("fuzzing with --seed {seed}");
+    let mut crashed = false;
+    for day in days {
+        // examples seed the corpus, along with earlier crashes so fixed ones stay covered
+        let mut seeds = Vec::new();
+        for file in inputs::list(day.name).map_err(|e| e.to_string())? {
+            if file.is_example() {
+                seeds.push(file.input().bytes().map_err(|e| e.to_string())?);
+            }
+        }
+        for path in fuzz::crash_files(day.name).map_err(|e| e.to_string())? {
+            seeds.push(fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?);
+        }
+
+        let report = fuzz::fuzz(day, &seeds, runs, seed);
+
+        println!(
+            "{:<8}{:>8} runs{:>4} crashes",
+            day.name,
+            report.runs,
+            report.crashes.len()
+        );
+        for crash in &report.crashes {
+            let path = fuzz::save(day.name, crash).map_err(|e| e.to_string())?;
+            println!("    {}: {}", path.display(), crash.message);
+        }
+        crashed |= !report.crashes.is_empty();
+    }
+
+    if crashed {
+        Err("some parsers panicked".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+fn generate(mut args: Args) -> Result<(), String> {
+    let seed = args.opt("seed")?;
+    let mut sizes = Vec::new();
+    while let Some(size) = args.opt("size")? {
+        let (k, v) = size
+            .split_once('=')
+            .ok_or_else(|| format!("--size: expected NAME=N, found {size:?}"))?;
+        let v = v.parse().map_err(|e| format!("--size {k}: {e}"))?;
+        sizes.push((k.to_owned(), v));
+    }
+    let output = args.opt("output")?;
+
+    // without a day, list what each generator takes
+    let Some(name) = args.positional() else {
+        args.finish()?;
+        if seed.is_some() || !sizes.is_empty() || output.is_some() {
+            return Err("missing day".to_owned());
+        }
+        for g in GENERATORS {
+            println!("{}:", g.day);
+            for s in g.sizes {
+                println!("    {:<12}{:>8}  {}", s.name, s.default, s.about);
+            }
+        }
+        return Ok(());
+    };
+
+    args.finish()?;
+    let generator = gen::find(&name).ok_or_else(|| format!("{name} has no input generator"))?;
+    let seed = match seed {
+        Some(s) => s.parse().map_err(|e| format!("--seed: {e}"))?,
+        None => 0,
+    };
+
+    let text = generator.generate(seed, &sizes)?;
+    match output {
+        Some(path) => fs::write(&path, text).map_err(|e| format!("{path}: {e}")),
+        None => {
+            print!("{text}");
+            Ok(())
+        }
+    }
+}
+
+fn list(args: Args) -> Result<(), String> {
+    args.finish()?;
+    for day in DAYS {
+        let files = inputs::list(day.name).map_err(|e| e.to_string())?;
+        let names: Vec<String> = files.into_iter().map(|f| f.name).collect();
+        println!("{}: {}", day.name, names.join(" "));
+        for p in day.params() {
+            let param = format!("{}={}", p.name, p.default);
+            println!("    --param {param:<32}{}", p.about);
+        }
+    }
+    Ok(())
+}
+
+fn attempt(mut args: Args) -> Result<(), String> {
+    let input = args.opt("input")?;
+    let mut positional = || args.positional();
+    let (Some(day), Some(part), Some(answer), Some(verdict)) =
+        (positional(), positional(), positional(), positional())
+    else {
+        return Err("expected <day> <part> <answer> <verdict>".to_owned());
+    };
+    if find_day(&day).is_none() {
+        return Err(format!("unknown day: {day}"));
+    }
+    let part: Part = part.parse().map_err(|e| format!("part: {e}"))?;
+    let verdict: Verdict = verdict.parse().map_err(|e| format!("verdict: {e}"))?;
+    let InputArg::Named(file) = resolve_input(&day, input.as_deref())? else {
+        return Err("attempts can only be recorded against a named input".to_owned());
+    };
+    args.finish()?;
+
+    let mut ledger = Ledger::load().map_err(|e| e.to_string())?;
+    warn(&ledger, &day, part, &file.name, &answer);
+    let attempt = Attempt {
+        day,
+        part,
+        input: file.name,
+        answer,
+        verdict,
+    };
+    let recorded = attempt.to_string();
+    ledger.record(attempt).map_err(|e| e.to_string())?;
+    println!("recorded {recorded}");
+    Ok(())
+}
+
+fn warn(ledger: &Ledger, day: &str, part: Part, input: &str, answer: &str) {
+    for w in ledger.check(day, part, input, answer) {
+        eprintln!("warning: {day} part {part} {input}: {w}");
+    }
+}
+
+pub enum InputArg {
+    Named(InputFile),
+    Path(PathBuf),
+    Stdin,
+}
+
+impl InputArg {
+    pub fn input(&self) -> Input<'static> {
+        match self {
+            Self::Named(file) => file.input(),
+            Self::Path(path) => Input::path(path),
+            Self::Stdin => Input::stdin(),
+        }
+    }
+}
+
+// `--input` names one of the day's inputs, a path relative to the working directory, or `-`
+pub fn resolve_input(day: &str, arg: Option<&str>) -> Result<InputArg, String> {
+    let file = match arg {
+        None => inputs::default(day).map_err(|e| e.to_string())?,
+        Some("-") => return Ok(InputArg::Stdin),
+        Some(arg) => match inputs::find(day, arg).map_err(|e| e.to_string())? {
+            Some(file) => file,
+            None => {
+                let cwd = env::current_dir().map_err(|e| e.to_string())?;
+                return Ok(InputArg::Path(cwd.join(arg)));
+            }
+        },
+    };
+    Ok(InputArg::Named(file))
+}
+
+fn run(mut args: Args) -> Result<(), String> {
+    let part = args.opt("part")?;
+    let input = args.opt("input")?;
+    let variant = args
+        .opt("variant")?
+        .unwrap_or_else(|| DEFAULT_VARIANT.to_owned());
+    let trace = args.opt("trace")?;
+    let explain = args.flag("explain");
+    let params = param_args(&mut args)?;
+    let name = args.positional().ok_or("missing day")?;
+    args.finish()?;
+
+    let day = find_day(&name).ok_or_else(|| format!("unknown day: {name}"))?;
+    let parts = match part {
+        None => Part::ALL.to_vec(),
+        Some(p) => vec![p.parse::<Part>().map_err(|e| format!("--part: {e}"))?],
+    };
+    let input = resolve_input(&name, input.as_deref())?;
+    let params = Params::with(day.params(), &params).map_err(|e| format!("--param {e}"))?;
+
+    for &part in &parts {
+        let variants = day.variants(part);
+        if !variants.contains(&variant.as_str()) {
+            let available = variants.join(", ");
+            return Err(format!(
+                "{name} part {part} has no variant {variant:?}, expected one of: {available}"
+            ));
+        }
+    }
+
+    let start = Instant::now();
+    let parsed = day.parse(input.input()).map_err(|e| e.to_string())?;
+    let ledger = Ledger::load().map_err(|e| e.to_string())?;
+    let parse_time = start.elapsed();
+
+    let solve = || {
+        for part in parts {
+            trace::emit("part", &[("n", &part)]);
+            let start = Instant::now();
+            let answer = day
+                .solve_with(parsed.as_ref(), part, &variant, &params)
+                .unwrap()
+                .map_err(|e| format!("{name} part {part}: {e}"))?;
+            let elapsed = parse_time + start.elapsed();
+
+            if answer.contains('\n') {
+                println!("{name} part {part} ({elapsed:?}):\n{answer}");
+            } else {
+                println!("{name} part {part}: {answer} ({elapsed:?})");
+            }
+            if let InputArg::Named(file) = &input {
+                warn(&ledger, &name, part, &file.name, &answer);
+            }
+            if explain {
+                match day.explain(parsed.as_ref(), part, &params) {
+                    Some(working) => println!("{working}"),
+                    None => println!("{name} can't explain its answers"),
+                }
+            }
+        }
+        Ok(())
+    };
+
+    match trace {
+        Some(path) => {
+            let file = File::create(&path).map_err(|e| format!("{path}: {e}"))?;
+            trace::record(file, solve).map_err(|e| format!("{path}: {e}"))?
+        }
+        None => solve(),
+    }
+}
+
+// every `--param NAME=VALUE`, in order
+pub fn param_args(args: &mut Args) -> Result<Vec<(String, String)>, String> {
+    let mut params = Vec::new();
+    while let Some(param) = args.opt("param")? {
+        let (name, value) = param
+            .split_once('=')
+            .ok_or_else(|| format!("--param: expected NAME=VALUE, found {param:?}"))?;
+        params.push((name.to_owned(), value.to_owned()));
+    }
+    Ok(params)
+}
+
+fn trace_diff(mut args: Args) -> Result<(), String> {
+    let context = match args.opt("context")? {
+        Some(n) => n.parse().map_err(|e| format!("--context: {e}"))?,
+        None => 5,
+    };
+    let (Some(a), Some(b)) = (args.positional(), args.positional()) else {
+        return Err("expected two traces".to_owned());
+    };
+    args.finish()?;
+
+    let read = |path: &str| fs::read_to_string(path).map_err(|e| format!("{path}: {e}"));
+    let (left, right) = (trace::parse(&read(&a)?), trace::parse(&read(&b)?));
+    let Some(d) = trace::diverge(&left, &right, context) else {
+        println!("traces match ({} events)", left.len());
+        return Ok(());
+    };
+
+    let line = |events: &[trace::Event]| {
+        events
+            .first()
+            .map_or("end".to_owned(), |e| e.line.to_string())
+    };
+    println!(
+        "traces diverge at event {} ({a}:{}, {b}:{})",
+        d.index + 1,
+        line(&d.left),
+        line(&d.right)
+    );
+    for e in &d.before {
+        println!("  {:>8}  {e}", e.line);
+    }
+    for (mark, path, events) in [('-', &a, &d.left), ('+', &b, &d.right)] {
+        if events.is_empty() {
+            println!("{mark} {:>8}  <{path} ends>", "");
+        }
+        for e in events {
+            println!("{mark} {:>8}  {e}", e.line);
+        }
+    }
+    for change in d.changes() {
+        println!("  {change}");
+    }
+    Err("traces differ".to_owned())
+}
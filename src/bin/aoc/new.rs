@@ -0,0 +1,110 @@
+use crate::args::Args;
+use aoc_2022::inputs;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = include_str!("day.rs.tpl");
+
+// Scaffolds a new day module and registers it. Safe to re-run: existing files are never touched
+// and a day already in the registry is left alone.
+pub fn new(mut args: Args) -> Result<(), String> {
+    let day = args.positional().ok_or("missing day")?;
+    args.finish()?;
+
+    let num = day
+        .strip_prefix("dec")
+        .filter(|n| n.len() == 2)
+        .and_then(|n| n.parse::<u8>().ok())
+        .filter(|n| (1..=25).contains(n))
+        .ok_or_else(|| format!("invalid day {day:?}, expected dec01 through dec25"))?;
+
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dir = root.join("src").join(&day);
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    let module = TEMPLATE.replace("__TYPE__", &format!("Dec{num:02}"));
+    create(&dir.join("mod.rs"), &module)?;
+    let user = env::var(inputs::USER_VAR)
+        .or_else(|_| env::var("USER"))
+        .unwrap_or_else(|_| "main".to_owned());
+    for set in [inputs::EXAMPLES, inputs::INPUTS] {
+        fs::create_dir_all(dir.join(set)).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    create(&dir.join(inputs::EXAMPLES).join("1.txt"), "")?;
+    create(&dir.join(inputs::INPUTS).join(format!("{user}.txt")), "")?;
+
+    let lib = root.join("src/lib.rs");
+    let src = fs::read_to_string(&lib).map_err(|e| format!("{}: {e}", lib.display()))?;
+    match register(&src, &day, num)? {
+        Some(updated) => {
+            fs::write(&lib, updated).map_err(|e| format!("{}: {e}", lib.display()))?;
+            println!("registered {day} in src/lib.rs");
+        }
+        None => println!("{day} is already registered"),
+    }
+
+    Ok(())
+}
+
+fn create(path: &Path, contents: &str) -> Result<(), String> {
+    if path.exists() {
+        println!("{} exists, leaving it alone", path.display());
+        return Ok(());
+    }
+
+    fs::write(path, contents).map_err(|e| format!("{}: {e}", path.display()))?;
+    println!("created {}", path.display());
+    Ok(())
+}
+
+// inserts the day into the `days!` invocation in lib.rs, keeping it sorted
+fn register(src: &str, day: &str, num: u8) -> Result<Option<String>, String> {
+    let start = src
+        .find("days! {\n")
+        .map(|i| i + "days! {\n".len())
+        .ok_or("could not find the days! registry in src/lib.rs")?;
+    let end = start
+        + src[start..]
+            .find("\n}")
+            .ok_or("unterminated days! registry in src/lib.rs")?;
+
+    let mut entries: Vec<&str> = src[start..end].lines().map(str::trim).collect();
+    if entries.iter().any(|e| e.starts_with(&format!("{day}::"))) {
+        return Ok(None);
+    }
+
+    let entry = format!("{day}::Dec{num:02},");
+    entries.push(&entry);
+    entries.sort();
+
+    let body: Vec<String> = entries.iter().map(|e| format!("    {e}")).collect();
+    Ok(Some(format!(
+        "{}{}{}",
+        &src[..start],
+        body.join("\n"),
+        &src[end..]
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str =
+        "mod utils;\n\ndays! {\n    dec01::Dec01,\n    dec03::Dec03,\n}\n\npub fn f() {}\n";
+
+    #[test]
+    fn registers_in_order() {
+        let out = register(LIB, "dec02", 2).unwrap().unwrap();
+        assert_eq!(
+            "mod utils;\n\ndays! {\n    dec01::Dec01,\n    dec02::Dec02,\n    dec03::Dec03,\n}\n\npub fn f() {}\n",
+            out
+        );
+    }
+
+    #[test]
+    fn idempotent() {
+        assert_eq!(None, register(LIB, "dec03", 3).unwrap());
+    }
+}
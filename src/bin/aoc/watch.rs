@@ -0,0 +1,200 @@
+use crate::args::Args;
+use crate::{param_args, resolve_input, InputArg};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL: Duration = Duration::from_millis(500);
+
+// Re-runs a day whenever its module directory or input changes. Solutions are rebuilt by
+// shelling out to `cargo run`, so edits to the code are picked up along with the inputs.
+pub fn watch(mut args: Args) -> Result<(), String> {
+    let part = args.opt("part")?;
+    let input = args.opt("input")?;
+    let params = param_args(&mut args)?;
+    let day = args.positional().ok_or("missing day")?;
+    args.finish()?;
+    if aoc_2022::find_day(&day).is_none() {
+        return Err(format!("unknown day: {day}"));
+    }
+
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut watched = vec![root.join("src").join(&day)];
+    // named inputs are passed through by name so `run` can check them against the ledger
+    let input = match resolve_input(&day, input.as_deref())? {
+        InputArg::Named(file) => {
+            watched.push(root.join(file.path()));
+            file.name
+        }
+        InputArg::Path(path) => {
+            watched.push(path.clone());
+            path.display().to_string()
+        }
+        InputArg::Stdin => return Err("can't watch stdin".to_owned()),
+    };
+
+    let mut run_args = vec!["run".to_owned(), day.clone(), "--input".to_owned(), input];
+    if let Some(p) = part {
+        run_args.extend(["--part".to_owned(), p]);
+    }
+    for (name, value) in params {
+        run_args.extend(["--param".to_owned(), format!("{name}={value}")]);
+    }
+
+    println!("watching {} (ctrl-c to stop)", watched[0].display());
+    let mut seen = scan(&watched);
+    let mut prev: Option<BTreeMap<String, String>> = None;
+    loop {
+        match execute(&root, &run_args) {
+            Some(answers) => {
+                print!("{}", diff(prev.as_ref(), &answers));
+                prev = Some(answers);
+            }
+            None => println!("run failed, waiting for changes"),
+        }
+
+        loop {
+            thread::sleep(POLL);
+            let now = scan(&watched);
+            if now != seen {
+                seen = now;
+                println!("\nchange detected, re-running {day}");
+                break;
+            }
+        }
+    }
+}
+
+// modification times of every file under `paths`, which may be files or directories
+fn scan(paths: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    let mut out = BTreeMap::new();
+    let mut stack: Vec<PathBuf> = paths.to_vec();
+    while let Some(p) = stack.pop() {
+        let Ok(meta) = fs::metadata(&p) else {
+            continue;
+        };
+
+        if meta.is_dir() {
+            if let Ok(entries) = fs::read_dir(&p) {
+                stack.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()));
+            }
+        } else if let Ok(mtime) = meta.modified() {
+            out.insert(p, mtime);
+        }
+    }
+    out
+}
+
+// builds and runs `aoc run` in a child process, returning its answers keyed by part
+fn execute(root: &Path, run_args: &[String]) -> Option<BTreeMap<String, String>> {
+    let manifest = root.join("Cargo.toml");
+    let output = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--bin", "aoc", "--manifest-path"])
+        .arg(manifest)
+        .arg("--")
+        .args(run_args)
+        .stderr(Stdio::inherit())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_answers(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Reads back the output of `aoc run`: either `<day> part <n>: <answer> (<time>)`, or
+// `<day> part <n> (<time>):` followed by a multi-line answer.
+fn parse_answers(out: &str) -> BTreeMap<String, String> {
+    let mut answers = BTreeMap::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in out.lines() {
+        let header = line
+            .split_once(" part ")
+            .filter(|(day, _)| day.starts_with("dec") && !day.contains(' '));
+
+        let Some((_, rest)) = header else {
+            if let Some((_, lines)) = &mut current {
+                lines.push(line);
+            }
+            continue;
+        };
+
+        if let Some((part, lines)) = current.take() {
+            answers.insert(part, lines.join("\n"));
+        }
+
+        let (part, rest) = rest.split_once([':', ' ']).unwrap_or((rest, ""));
+        let answer = rest.trim().rsplit_once(" (").map_or("", |(a, _)| a);
+        if rest.ends_with(':') {
+            current = Some((part.to_owned(), Vec::new()));
+        } else {
+            answers.insert(part.to_owned(), answer.to_owned());
+        }
+    }
+
+    if let Some((part, lines)) = current {
+        answers.insert(part, lines.join("\n"));
+    }
+    answers
+}
+
+fn diff(prev: Option<&BTreeMap<String, String>>, next: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    for (part, answer) in next {
+        let old = prev.and_then(|p| p.get(part));
+        match old {
+            Some(old) if old == answer => out += &format!("part {part}: unchanged\n"),
+            Some(old) if !old.contains('\n') && !answer.contains('\n') => {
+                out += &format!("part {part}: {old} -> {answer}\n")
+            }
+            Some(old) => {
+                out += &format!("part {part}: changed\n");
+                let (old, new): (Vec<&str>, Vec<&str>) =
+                    (old.lines().collect(), answer.lines().collect());
+                for idx in 0..old.len().max(new.len()) {
+                    match (old.get(idx), new.get(idx)) {
+                        (Some(o), Some(n)) if o == n => out += &format!("  {n}\n"),
+                        (o, n) => {
+                            if let Some(o) = o {
+                                out += &format!("- {o}\n");
+                            }
+                            if let Some(n) = n {
+                                out += &format!("+ {n}\n");
+                            }
+                        }
+                    }
+                }
+            }
+            None if answer.contains('\n') => out += &format!("part {part}:\n{answer}\n"),
+            None => out += &format!("part {part}: {answer}\n"),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_round_trip() {
+        let out = "dec10 part 1: 13140 (1.2ms)\ndec10 part 2 (3µs):\n##..\n#.#.\n";
+        let answers = parse_answers(out);
+        assert_eq!("13140", answers["1"]);
+        assert_eq!("##..\n#.#.", answers["2"]);
+
+        let mut next = answers.clone();
+        next.insert("1".to_owned(), "42".to_owned());
+        next.insert("2".to_owned(), "##..\n###.".to_owned());
+        assert_eq!(
+            "part 1: 13140 -> 42\npart 2: changed\n  ##..\n- #.#.\n+ ###.\n",
+            diff(Some(&answers), &next)
+        );
+        assert!(diff(Some(&next), &next).starts_with("part 1: unchanged\n"));
+    }
+}
@@ -0,0 +1,256 @@
+use crate::utils::{Input, Line};
+use crate::{Error, Result};
+use std::fmt::{Display, Write};
+use std::iter::Peekable;
+use std::str::FromStr;
+
+// Saved state of a long-running simulation, so it can be stopped partway and resumed later, or
+// set up for a test from a fixture. A checkpoint is text, one record per line:
+//
+//   checkpoint <kind>
+//   <record> <field> <field>...
+//
+// Fields are written with `Display` and must not contain whitespace; the last fields of a record
+// may be a list of any length. Each simulation writes its records in a fixed order and reads them
+// back in the same order.
+const HEADER: &str = "checkpoint";
+
+pub trait Checkpoint: Sized {
+    // named in the header, so one simulation's state can't be loaded as another's
+    const KIND: &'static str;
+
+    fn save(&self, out: &mut Writer);
+
+    fn load(records: &mut Records) -> Result<Self>;
+}
+
+pub fn save<T: Checkpoint>(state: &T) -> String {
+    let mut out = Writer(String::new());
+    out.record(HEADER, &[&T::KIND]);
+    state.save(&mut out);
+    out.0
+}
+
+pub fn load<T: Checkpoint>(input: Input) -> Result<T> {
+    let name = input.name();
+    let load = || {
+        let lines: Vec<Line> = input.lines()?.filter(|l| !l.trim().is_empty()).collect();
+        let mut records = Records {
+            lines: lines.into_iter().peekable(),
+            last: 0,
+        };
+
+        let header = records.next(HEADER)?;
+        let kind = header.text(0)?;
+        if kind != T::KIND {
+            let reason = format!("expected a {} checkpoint, found {kind:?}", T::KIND);
+            return Err(header.error(0, reason));
+        }
+
+        let state = T::load(&mut records)?;
+        match records.lines.next() {
+            Some(line) => Err(line.error(1, "unexpected record")),
+            None => Ok(state),
+        }
+    };
+    load().map_err(|e| e.in_file(&name))
+}
+
+pub struct Writer(String);
+
+impl Writer {
+    pub fn record(&mut self, name: &str, fields: &[&dyn Display]) {
+        self.list(name, fields, [] as [&str; 0]);
+    }
+
+    // a record ending in every item of `list`
+    pub fn list<T: Display>(
+        &mut self,
+        name: &str,
+        fields: &[&dyn Display],
+        list: impl IntoIterator<Item = T>,
+    ) {
+        self.0.push_str(name);
+        for field in fields {
+            write!(self.0, " {field}").unwrap();
+        }
+        for item in list {
+            write!(self.0, " {item}").unwrap();
+        }
+        self.0.push('\n');
+    }
+}
+
+pub struct Records {
+    lines: Peekable<std::vec::IntoIter<Line>>,
+    // line number of the last record read, for reporting one missing after it
+    last: usize,
+}
+
+impl Records {
+    // the next record, which must be called `name`
+    pub fn next(&mut self, name: &str) -> Result<Record> {
+        if let Some(line) = self.lines.next_if(|l| Record::name_of(l) == name) {
+            self.last = line.no;
+            return Ok(Record(line));
+        }
+        match self.lines.peek() {
+            Some(line) => {
+                let found = Record::name_of(line);
+                Err(line.error(1, format!("expected {name:?}, found {found:?}")))
+            }
+            None => Err(Error::parse(1, format!("expected {name:?}")).at_line(self.last + 1)),
+        }
+    }
+
+    // the records called `name` from here on, up to the first called anything else
+    pub fn all(&mut self, name: &str) -> Vec<Record> {
+        let mut out = Vec::new();
+        while let Some(line) = self.lines.next_if(|l| Record::name_of(l) == name) {
+            self.last = line.no;
+            out.push(Record(line));
+        }
+        out
+    }
+}
+
+pub struct Record(Line);
+
+impl Record {
+    fn name_of(line: &str) -> &str {
+        line.split_whitespace().next().unwrap_or_default()
+    }
+
+    pub fn line(&self) -> usize {
+        self.0.no
+    }
+
+    fn fields(&self) -> impl Iterator<Item = &str> {
+        self.0.split_whitespace().skip(1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // the field at `idx`, counting from the one after the name
+    pub fn text(&self, idx: usize) -> Result<&str> {
+        self.fields().nth(idx).ok_or_else(|| {
+            let reason = format!("missing field {}", idx + 1);
+            self.0.error(self.0.len() + 1, reason)
+        })
+    }
+
+    pub fn field<T>(&self, idx: usize) -> Result<T>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Into<Error>,
+    {
+        self.parse(self.text(idx)?)
+    }
+
+    // parses `text`, which must be part of a field, reporting failures at its column
+    pub fn parse<T>(&self, text: &str) -> Result<T>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Into<Error>,
+    {
+        self.0.parse_field(text)
+    }
+
+    // every field from `idx` on
+    pub fn rest<T>(&self, idx: usize) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Into<Error>,
+    {
+        self.fields()
+            .skip(idx)
+            .map(|f| self.0.parse_field(f))
+            .collect()
+    }
+
+    // an error at the field at `idx`, or the end of the record if there's no such field
+    pub fn error(&self, idx: usize, reason: impl Into<String>) -> Error {
+        match self.fields().nth(idx) {
+            Some(field) => self.0.field_error(field, reason),
+            None => self.0.error(self.0.len() + 1, reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter {
+        ticks: usize,
+        marks: Vec<isize>,
+    }
+
+    impl Checkpoint for Counter {
+        const KIND: &'static str = "counter";
+
+        fn save(&self, out: &mut Writer) {
+            out.list("ticks", &[&self.ticks], &self.marks);
+        }
+
+        fn load(records: &mut Records) -> Result<Self> {
+            let ticks = records.next("ticks")?;
+            Ok(Self {
+                ticks: ticks.field(0)?,
+                marks: ticks.rest(1)?,
+            })
+        }
+    }
+
+    fn load_str(text: &str) -> Result<Counter, String> {
+        load(Input::Str(text)).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn round_trips() {
+        let text = save(&Counter {
+            ticks: 3,
+            marks: vec![1, -2],
+        });
+        assert_eq!("checkpoint counter\nticks 3 1 -2\n", text);
+
+        let counter = load_str(&text).unwrap();
+        assert_eq!((3, vec![1, -2]), (counter.ticks, counter.marks));
+        assert!(load_str("checkpoint counter\n\nticks 0\n")
+            .unwrap()
+            .marks
+            .is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let err = |text| load_str(text).err().unwrap();
+        assert_eq!(
+            "<input>:1:12: expected a counter checkpoint, found \"wall\"",
+            err("checkpoint wall\nticks 3\n")
+        );
+        assert_eq!(
+            "<input>:2:1: expected \"ticks\"",
+            err("checkpoint counter\n")
+        );
+        assert_eq!(
+            "<input>:2:1: expected \"ticks\", found \"tocks\"",
+            err("checkpoint counter\ntocks 3\n")
+        );
+        assert_eq!(
+            "<input>:2:6: missing field 1",
+            err("checkpoint counter\nticks\n")
+        );
+        assert!(err("checkpoint counter\nticks 3 x\n").starts_with("<input>:2:9: "));
+        assert_eq!(
+            "<input>:3:1: unexpected record",
+            err("checkpoint counter\nticks 3\nticks 4\n")
+        );
+    }
+}
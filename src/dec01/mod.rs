@@ -1,23 +1,75 @@
+use crate::params::{Param, Params};
 use crate::utils::*;
+use crate::{Error, Result, Solution, Variant};
 use std::cmp::Reverse;
 use std::collections::binary_heap::BinaryHeap;
 
-fn total_calories(input: &str) -> impl Iterator<Item = usize> + '_ {
-    file_groups(input)
-        .map(parse_items::<usize>)
-        .map(Iterator::sum)
+pub struct Dec01;
+
+impl Solution for Dec01 {
+    type Input = Vec<usize>;
+    type Output1 = usize;
+    type Output2 = Result<usize>;
+
+    const VARIANTS_2: &'static [Variant<Vec<usize>, Result<usize>>] =
+        &[("sort", |totals, p| top_n_sorted(totals, p.int("top")))];
+
+    const PARAMS: &'static [Param] = &[Param::int("top", 3, "elves whose calories part 2 adds up")];
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        let totals: Vec<usize> = input
+            .groups()?
+            .map(|mut group| {
+                group.try_fold(0usize, |total, line| {
+                    let cals: usize = line.parse()?;
+                    total
+                        .checked_add(cals)
+                        .ok_or_else(|| line.error(1, "calorie total overflows"))
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        if totals.is_empty() {
+            return Err(Error::parse(1, "expected at least one elf").at_line(1));
+        }
+        Ok(totals)
+    }
+
+    // parse guarantees there's at least one elf
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input.iter().copied().max().unwrap_or_default()
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        Self::part_2_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn part_2_with(input: &Self::Input, params: &Params) -> Self::Output2 {
+        top_n(input, params.int("top"))
+    }
 }
 
-fn solve_1(input: &str) -> usize {
-    total_calories(input).max().unwrap()
+// the totals each fit, but adding them up may not
+fn sum(top: impl Iterator<Item = usize>, n: usize) -> Result<usize> {
+    let mut total: usize = 0;
+    for cals in top {
+        total = total
+            .checked_add(cals)
+            .ok_or_else(|| Error::unsolvable(format!("the top {n} calorie totals overflow")))?;
+    }
+    Ok(total)
 }
 
-fn solve_2(input: &str, n: usize) -> usize {
-    let mut heap = BinaryHeap::with_capacity(n);
-    let cals = total_calories(input).map(Reverse);
+pub fn top_n(totals: &[usize], n: usize) -> Result<usize> {
+    if n == 0 {
+        return Ok(0);
+    }
+
+    let mut heap = BinaryHeap::with_capacity(n.min(totals.len()));
+    let cals = totals.iter().copied().map(Reverse);
     for ttl in cals {
         if heap.len() >= n {
-            if let Some(Reverse(prev)) = heap.peek() && *prev >= ttl.0 {
+            if matches!(heap.peek(), Some(Reverse(prev)) if *prev >= ttl.0) {
                 continue;
             }
             heap.pop();
@@ -25,34 +77,58 @@ fn solve_2(input: &str, n: usize) -> usize {
         heap.push(ttl);
     }
 
-    heap.into_iter().map(|r| r.0).sum()
+    sum(heap.into_iter().map(|r| r.0), n)
+}
+
+// sorts everything rather than keeping a bounded heap
+pub fn top_n_sorted(totals: &[usize], n: usize) -> Result<usize> {
+    let mut totals = totals.to_vec();
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    sum(totals.into_iter().take(n), n)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, vecs};
 
-    #[test]
-    fn example_1() {
-        let input = "src/dec01/example_1.txt";
-        assert_eq!(24000, solve_1(input))
+    fn naive_top_n(totals: &[usize], n: usize) -> usize {
+        let mut sorted = totals.to_vec();
+        sorted.sort();
+        sorted.iter().rev().take(n).sum()
     }
 
     #[test]
-    fn puzzle_1() {
-        let input = "src/dec01/input_1.txt";
-        assert_eq!(71780, solve_1(input))
+    fn matches_reference() {
+        let elves = vecs(vecs(ints(1usize..=9999), 1..=4), 1..=12);
+        check("dec01", (elves, ints(0usize..=5)), |(elves, n)| {
+            let text: Vec<String> = elves
+                .iter()
+                .map(|e| e.iter().map(usize::to_string).collect::<Vec<_>>().join("\n"))
+                .collect();
+            let totals = Dec01::parse(Input::Str(&text.join("\n\n"))).map_err(|e| e.to_string())?;
+
+            let expected: Vec<usize> = elves.iter().map(|e| e.iter().sum()).collect();
+            expect_eq("totals", &expected, &totals)?;
+            expect_eq("part 1", naive_top_n(&expected, 1), Dec01::part_1(&totals))?;
+            expect_eq("top_n", naive_top_n(&expected, *n), top_n(&totals, *n).unwrap())
+        });
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec01/example_1.txt";
-        assert_eq!(45000, solve_2(input, 3))
+    fn no_elves() {
+        for input in ["", "\n\n"] {
+            let err = Dec01::parse(input.into()).err().unwrap();
+            assert_eq!("line 1, column 1: expected at least one elf", err.to_string());
+        }
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec01/input_1.txt";
-        assert_eq!(212489, solve_2(input, 3))
+    fn overflow() {
+        let totals = [usize::MAX - 1, 1, 1];
+        assert_eq!(usize::MAX, top_n(&totals, 2).unwrap());
+        let err = top_n(&totals, 3).err().unwrap();
+        assert_eq!("no answer: the top 3 calorie totals overflow", err.to_string());
+        assert!(top_n_sorted(&totals, 3).is_err());
     }
 }
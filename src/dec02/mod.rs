@@ -1,100 +1,183 @@
 use crate::utils::*;
+use crate::{Error, Result, Solution};
+use std::str::FromStr;
 
-#[derive(Copy, Clone)]
-struct Shape(i8);
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Shape(i8);
 
 impl Shape {
-    fn against(self, opp: Self) -> Outcome {
+    pub fn against(self, opp: Self) -> Outcome {
         Outcome((self.0 - opp.0).rem_euclid(3))
     }
 
-    fn my_play(self, out: Outcome) -> Self {
+    pub fn my_play(self, out: Outcome) -> Self {
         Self((self.0 + out.0).rem_euclid(3))
     }
 
-    fn score(self) -> isize {
+    pub fn score(self) -> isize {
         (1 + self.0) as isize
     }
 }
 
-impl From<&str> for Shape {
-    fn from(value: &str) -> Self {
+impl FromStr for Shape {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
         match value {
-            "A" | "X" => Self(0), // rock
-            "B" | "Y" => Self(1), // paper
-            "C" | "Z" => Self(2), // scissors
-            _ => unreachable!(),
+            "A" | "X" => Ok(Self(0)), // rock
+            "B" | "Y" => Ok(Self(1)), // paper
+            "C" | "Z" => Ok(Self(2)), // scissors
+            _ => Err(Error::parse(
+                1,
+                format!("expected a shape, found {value:?}"),
+            )),
         }
     }
 }
 
 #[derive(Copy, Clone)]
-struct Outcome(i8);
+pub struct Outcome(i8);
 
 impl Outcome {
-    fn score(self) -> isize {
+    pub fn score(self) -> isize {
         (((1 + self.0) % 3) * 3) as isize
     }
 }
 
-impl From<&str> for Outcome {
-    fn from(value: &str) -> Self {
+impl FromStr for Outcome {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
         match value {
-            "X" => Self(2), // lose
-            "Y" => Self(0), // draw
-            "Z" => Self(1), // win
-            _ => unreachable!(),
+            "X" => Ok(Self(2)), // lose
+            "Y" => Ok(Self(0)), // draw
+            "Z" => Ok(Self(1)), // win
+            _ => Err(Error::parse(
+                1,
+                format!("expected an outcome, found {value:?}"),
+            )),
         }
     }
 }
 
-fn solve_1(input: &str) -> isize {
-    file_lines(input)
-        .map(|s| {
-            let (opp, me) = s.split_once(' ').unwrap();
-            (Shape::from(opp), Shape::from(me))
-        })
-        .map(|(opp, me)| (me, me.against(opp)))
-        .map(|(me, out)| me.score() + out.score())
-        .sum()
+// the second column is either my shape or the desired outcome, depending on the part
+pub struct Round {
+    opp: Shape,
+    me: Shape,
+    out: Outcome,
+}
+
+impl Round {
+    pub fn opponent(&self) -> Shape {
+        self.opp
+    }
+
+    pub fn me(&self) -> Shape {
+        self.me
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.out
+    }
 }
 
-fn solve_2(input: &str) -> isize {
-    file_lines(input)
-        .map(|s| {
-            let (opp, out) = s.split_once(' ').unwrap();
-            (Shape::from(opp), Outcome::from(out))
+impl FromStr for Round {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let (opp, col) = value
+            .split_once(' ')
+            .ok_or_else(|| Error::parse(value.len() + 1, "expected two columns"))?;
+
+        Ok(Self {
+            opp: parse_field(value, opp)?,
+            me: parse_field(value, col)?,
+            out: parse_field(value, col)?,
         })
-        .map(|(opp, out)| (opp.my_play(out), out))
-        .map(|(me, out)| me.score() + out.score())
-        .sum()
+    }
+}
+
+pub struct Dec02;
+
+impl Solution for Dec02 {
+    type Input = Vec<Round>;
+    type Output1 = isize;
+    type Output2 = isize;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        parse_items(input.lines()?).collect()
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input
+            .iter()
+            .map(|r| (r.me, r.me.against(r.opp)))
+            .map(|(me, out)| me.score() + out.score())
+            .sum()
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        input
+            .iter()
+            .map(|r| (r.opp.my_play(r.out), r.out))
+            .map(|(me, out)| me.score() + out.score())
+            .sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, vecs};
 
-    #[test]
-    fn example_1() {
-        let input = "src/dec02/example_1.txt";
-        assert_eq!(15, solve_1(input))
+    // shapes and outcomes by index: rock, paper, scissors; lose, draw, win
+    fn beats(a: usize, b: usize) -> bool {
+        matches!((a, b), (0, 2) | (1, 0) | (2, 1))
     }
 
-    #[test]
-    fn puzzle_1() {
-        let input = "src/dec02/input_1.txt";
-        assert_eq!(11841, solve_1(input))
+    fn score(me: usize, opp: usize) -> isize {
+        let outcome = if beats(me, opp) {
+            6
+        } else if me == opp {
+            3
+        } else {
+            0
+        };
+        me as isize + 1 + outcome
     }
 
-    #[test]
-    fn example_2() {
-        let input = "src/dec02/example_1.txt";
-        assert_eq!(12, solve_2(input))
+    // tries every shape until one gives the wanted outcome
+    fn naive(rounds: &[(usize, usize)]) -> (isize, isize) {
+        let part_1 = rounds.iter().map(|&(opp, col)| score(col, opp)).sum();
+        let part_2 = rounds
+            .iter()
+            .map(|&(opp, col)| {
+                let me = (0..3)
+                    .find(|&me| match col {
+                        0 => beats(opp, me),
+                        1 => me == opp,
+                        _ => beats(me, opp),
+                    })
+                    .unwrap();
+                score(me, opp)
+            })
+            .sum();
+        (part_1, part_2)
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec02/input_1.txt";
-        assert_eq!(13022, solve_2(input))
+    fn matches_reference() {
+        let rounds = vecs((ints(0usize..=2), ints(0usize..=2)), 0..=20);
+        check("dec02", rounds, |rounds| {
+            let text: Vec<String> = rounds
+                .iter()
+                .map(|&(opp, col)| format!("{} {}", ["A", "B", "C"][opp], ["X", "Y", "Z"][col]))
+                .collect();
+            let input = Dec02::parse(Input::Str(&text.join("\n"))).map_err(|e| e.to_string())?;
+
+            let (part_1, part_2) = naive(rounds);
+            expect_eq("part 1", part_1, Dec02::part_1(&input))?;
+            expect_eq("part 2", part_2, Dec02::part_2(&input))
+        });
     }
 }
@@ -1,12 +1,28 @@
 use crate::utils::*;
+use crate::{Error, Result, Solution};
 use std::collections::HashSet;
 use std::hash::Hash;
 
+fn rucksack(line: Line) -> Result<String> {
+    if let Some((idx, ch)) = line
+        .char_indices()
+        .find(|(_, ch)| !ch.is_ascii_alphabetic())
+    {
+        return Err(line.error(idx + 1, format!("expected an item letter, found {ch:?}")));
+    }
+
+    if !line.len().is_multiple_of(2) {
+        return Err(line.error(1, "compartments must hold the same number of items"));
+    }
+
+    Ok(line.into_string())
+}
+
 fn char_set(s: &str) -> HashSet<char> {
     s.chars().collect()
 }
 
-fn priority(ch: char) -> u32 {
+pub fn priority(ch: char) -> u32 {
     if ch.is_ascii_lowercase() {
         ch as u32 - 96
     } else {
@@ -28,51 +44,100 @@ where
     two_way_intersection(a, b).filter(move |el| c.contains(el))
 }
 
-fn solve_1(input: &str) -> u32 {
-    file_lines(input)
-        .map(|l| {
-            let (a, b) = l.split_at(l.len() / 2);
-            (char_set(a), char_set(b))
-        })
-        .flat_map(|(a, b)| two_way_intersection(a, b))
-        .map(priority)
-        .sum()
-}
+pub struct Dec03;
+
+impl Solution for Dec03 {
+    type Input = Vec<String>;
+    type Output1 = u32;
+    type Output2 = Result<u32>;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        input.lines()?.map(rucksack).collect()
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input
+            .iter()
+            .map(|l| {
+                let (a, b) = l.split_at(l.len() / 2);
+                (char_set(a), char_set(b))
+            })
+            .flat_map(|(a, b)| two_way_intersection(a, b))
+            .map(priority)
+            .sum()
+    }
 
-fn solve_2(input: &str) -> u32 {
-    file_lines(input)
-        .map(|l| char_set(&l))
-        .array_chunks()
-        .flat_map(|[a, b, c]| three_way_intersection(a, b, c))
-        .map(priority)
-        .sum()
+    // every line is a rucksack, so a partial group starts at the line after the last full one
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        let extra = input.len() % 3;
+        if extra != 0 {
+            let reason = format!("elves come in groups of 3, but the last group has {extra}");
+            return Err(Error::parse(1, reason).at_line(input.len() - extra + 1));
+        }
+
+        Ok(input
+            .iter()
+            .map(|l| char_set(l))
+            .arrays()
+            .flat_map(|[a, b, c]| three_way_intersection(a, b, c))
+            .map(priority)
+            .sum())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, pick, vecs};
 
-    #[test]
-    fn example_1() {
-        let input = "src/dec03/example_1.txt";
-        assert_eq!(157, solve_1(input))
-    }
+    const PRIORITIES: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
-    #[test]
-    fn puzzle_1() {
-        let input = "src/dec03/input_1.txt";
-        assert_eq!(7581, solve_1(input))
+    // sums the priority of every letter found in all of `sets`
+    fn shared(sets: &[&str]) -> u32 {
+        PRIORITIES
+            .chars()
+            .zip(1..)
+            .filter(|&(ch, _)| sets.iter().all(|s| s.contains(ch)))
+            .map(|(_, p)| p)
+            .sum()
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec03/example_1.txt";
-        assert_eq!(70, solve_2(input))
+    fn partial_group() {
+        let input = Dec03::parse("abab\ncdcd\nacac\nbdbd\n".into()).unwrap();
+        let err = Dec03::part_2(&input).err().unwrap();
+        assert_eq!(
+            "line 4, column 1: elves come in groups of 3, but the last group has 1",
+            err.to_string()
+        );
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec03/input_1.txt";
-        assert_eq!(2525, solve_2(input))
+    fn matches_reference() {
+        let items = pick(&['a', 'b', 'm', 'z', 'A', 'M', 'Z']);
+        check("dec03", vecs(vecs(items, 2..=12), 0..=9), |sacks| {
+            // compartments are the same size, so drop the last item of an odd rucksack
+            let lines: Vec<String> = sacks
+                .iter()
+                .map(|s| s[..s.len() / 2 * 2].iter().collect())
+                .collect();
+            let input = Dec03::parse(Input::Str(&lines.join("\n"))).map_err(|e| e.to_string())?;
+
+            let part_1 = lines
+                .iter()
+                .map(|l| {
+                    let (a, b) = l.split_at(l.len() / 2);
+                    shared(&[a, b])
+                })
+                .sum();
+            let part_2 = lines
+                .chunks_exact(3)
+                .map(|g| shared(&[&g[0], &g[1], &g[2]]))
+                .sum();
+            expect_eq("part 1", part_1, Dec03::part_1(&input))?;
+            // a partial group is an error rather than being left out
+            let part_2 = Some(part_2).filter(|_| lines.len().is_multiple_of(3));
+            expect_eq("part 2", part_2, Dec03::part_2(&input).ok())
+        });
     }
 }
@@ -1,7 +1,11 @@
+use crate::params::Params;
 use crate::utils::*;
+use crate::{Error, Part, Result, Solution};
+use std::fmt::{Display, Formatter};
 use std::ops::RangeInclusive;
+use std::str::FromStr;
 
-struct Pair(RangeInclusive<u32>, RangeInclusive<u32>);
+pub struct Pair(RangeInclusive<u32>, RangeInclusive<u32>);
 
 impl Pair {
     fn includes(outer: &RangeInclusive<u32>, inner: &RangeInclusive<u32>) -> bool {
@@ -12,64 +16,147 @@ impl Pair {
         first.contains(second.start()) || first.contains(second.end())
     }
 
-    fn any_overlap(&self) -> bool {
+    pub fn any_overlap(&self) -> bool {
         Self::overlaps(&self.0, &self.1) || Self::overlaps(&self.1, &self.0)
     }
 
-    fn full_overlap(&self) -> bool {
+    pub fn full_overlap(&self) -> bool {
         Self::includes(&self.0, &self.1) || Self::includes(&self.1, &self.0)
     }
+
+    // why the pair counts towards `part`, if it does
+    fn explain(&self, part: Part) -> Option<String> {
+        let show = |r: &RangeInclusive<u32>| format!("{}-{}", r.start(), r.end());
+        match part {
+            Part::One if Self::includes(&self.0, &self.1) => {
+                Some(format!("{} contains {}", show(&self.0), show(&self.1)))
+            }
+            Part::One if Self::includes(&self.1, &self.0) => {
+                Some(format!("{} contains {}", show(&self.1), show(&self.0)))
+            }
+            Part::Two if self.any_overlap() => {
+                let shared = *self.0.start().max(self.1.start())..=*self.0.end().min(self.1.end());
+                Some(format!("both have {}", show(&shared)))
+            }
+            _ => None,
+        }
+    }
 }
 
-impl From<String> for Pair {
-    fn from(value: String) -> Self {
-        let mut iter = value
-            .splitn(4, &[',', '-'])
-            .array_chunks()
-            .map(|[start, end]| must_parse::<u32>(start)..=must_parse::<u32>(end));
-        Pair(iter.next().unwrap(), iter.next().unwrap())
+impl Display for Pair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (a, b) = (&self.0, &self.1);
+        write!(f, "{}-{},{}-{}", a.start(), a.end(), b.start(), b.end())
     }
 }
 
-fn solve_1(input: &str) -> usize {
-    file_lines(input)
-        .map(Pair::from)
-        .filter(Pair::full_overlap)
-        .count()
+impl Pair {
+    fn range(value: &str, field: &str) -> Result<RangeInclusive<u32>> {
+        let (start, end) = field.split_once('-').ok_or_else(|| {
+            Error::parse(
+                col_of(value, field),
+                format!("expected a range, found {field:?}"),
+            )
+        })?;
+        Ok(parse_field(value, start)?..=parse_field(value, end)?)
+    }
 }
 
-fn solve_2(input: &str) -> usize {
-    file_lines(input)
-        .map(Pair::from)
-        .filter(Pair::any_overlap)
-        .count()
+impl FromStr for Pair {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let (first, second) = value
+            .split_once(',')
+            .ok_or_else(|| Error::parse(value.len() + 1, "expected two comma-separated ranges"))?;
+        Ok(Pair(
+            Self::range(value, first)?,
+            Self::range(value, second)?,
+        ))
+    }
+}
+
+pub struct Dec04;
+
+impl Solution for Dec04 {
+    type Input = Vec<Pair>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        parse_items(input.lines()?).collect()
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input.iter().filter(|p| p.full_overlap()).count()
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        input.iter().filter(|p| p.any_overlap()).count()
+    }
+
+    // lists the pairs that count, by line
+    fn explain(input: &Self::Input, part: Part, _params: &Params) -> Option<String> {
+        let lines: Vec<String> = input
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, p)| Some(format!("line {}: {p}: {}", idx + 1, p.explain(part)?)))
+            .collect();
+        let counted = match part {
+            Part::One => "one range contains the other",
+            Part::Two => "the ranges overlap",
+        };
+        let (n, lines) = (lines.len(), lines.join("\n"));
+        Some(format!("{n} pairs where {counted}:\n{lines}"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, vecs};
 
     #[test]
-    fn example_1() {
-        let input = "src/dec04/example_1.txt";
-        assert_eq!(2, solve_1(input));
+    fn parse_error() {
+        let err = "2-4,6-x".parse::<Pair>().err().unwrap();
+        assert_eq!("column 7: invalid digit found in string", err.to_string());
     }
 
-    #[test]
-    fn puzzle_1() {
-        let input = "src/dec04/input_1.txt";
-        assert_eq!(515, solve_1(input));
+    // compares every section of one range against the other
+    fn naive_overlaps(a: (u32, u32), b: (u32, u32)) -> (bool, bool) {
+        let within = |s: u32, (lo, hi): (u32, u32)| (lo..=hi).contains(&s);
+        let full = (a.0..=a.1).all(|s| within(s, b)) || (b.0..=b.1).all(|s| within(s, a));
+        let any = (a.0..=a.1).any(|s| within(s, b));
+        (full, any)
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec04/example_1.txt";
-        assert_eq!(4, solve_2(input));
-    }
+    fn matches_reference() {
+        let bound = || ints(1u32..=20);
+        let pairs = vecs((bound(), bound(), bound(), bound()), 0..=20);
+        check("dec04", pairs, |pairs| {
+            let ranges: Vec<((u32, u32), (u32, u32))> = pairs
+                .iter()
+                .map(|&(a, b, c, d)| ((a.min(b), a.max(b)), (c.min(d), c.max(d))))
+                .collect();
 
-    #[test]
-    fn puzzle_2() {
-        let input = "src/dec04/input_1.txt";
-        assert_eq!(883, solve_2(input));
+            let (mut full, mut any) = (0, 0);
+            for &(a, b) in &ranges {
+                let line = format!("{}-{},{}-{}", a.0, a.1, b.0, b.1);
+                let pair: Pair = line.parse().map_err(|e: Error| e.to_string())?;
+                let expected = naive_overlaps(a, b);
+                expect_eq(&line, expected, (pair.full_overlap(), pair.any_overlap()))?;
+                full += expected.0 as usize;
+                any += expected.1 as usize;
+            }
+
+            let text: Vec<String> = ranges
+                .iter()
+                .map(|(a, b)| format!("{}-{},{}-{}", a.0, a.1, b.0, b.1))
+                .collect();
+            let input = Dec04::parse(Input::Str(&text.join("\n"))).map_err(|e| e.to_string())?;
+            expect_eq("part 1", full, Dec04::part_1(&input))?;
+            expect_eq("part 2", any, Dec04::part_2(&input))
+        });
     }
 }
@@ -1,27 +1,58 @@
 use crate::utils::*;
+use crate::{Error, Result, Solution};
+use std::str::FromStr;
 use std::vec::Vec;
 
-#[derive(Default)]
-struct Stack(Vec<Vec<char>>);
+#[derive(Clone, Default)]
+pub struct Stack(Vec<Vec<char>>);
 
 impl Stack {
-    fn new<I: Iterator<Item = String>>(iter: &mut I) -> Self {
+    fn new<I: Iterator<Item = Line>>(iter: &mut I) -> Result<Self> {
         let mut s = Self::default();
-        for line in iter {
+        let mut last = 0;
+        for line in iter.by_ref() {
+            if line.trim_start().starts_with(|c: char| c.is_ascii_digit()) {
+                s.add_labels(&line)?;
+                for v in &mut s.0 {
+                    v.reverse()
+                }
+
+                return match iter.next() {
+                    Some(l) if !l.is_empty() => Err(l.error(1, "expected a blank line")),
+                    _ => Ok(s),
+                };
+            }
             if line.is_empty() {
                 break;
             }
-            s.add_line(line)
+            last = line.no;
+            s.add_line(&line)?
+        }
+
+        Err(Error::parse(1, "expected a line of stack numbers").at_line(last + 1))
+    }
+
+    // ` 1   2   3` numbers every stack, including those that start out empty
+    fn add_labels(&mut self, line: &Line) -> Result<()> {
+        let mut n = 0;
+        for label in line.split_whitespace() {
+            n += 1;
+            if line.parse_field::<usize>(label)? != n {
+                return Err(line.field_error(label, format!("expected stack {n}")));
+            }
         }
 
-        for v in &mut s.0 {
-            v.reverse()
+        let found = self.0.len().saturating_sub(1);
+        if found > n {
+            let reason = format!("expected {found} stacks, found {n}");
+            return Err(line.error(line.len() + 1, reason));
         }
-        s
+        self.0.resize(n + 1, Vec::new());
+        Ok(())
     }
 
-    fn add_line(&mut self, line: String) {
-        let mut chars = line.chars();
+    fn add_line(&mut self, line: &Line) -> Result<()> {
+        let mut chars = line.char_indices();
         let mut idx = 0;
 
         loop {
@@ -31,28 +62,63 @@ impl Stack {
                 break;
             }
 
-            let val = chars.next().unwrap();
-            if delim == Some('[') {
+            let val = chars.next();
+            if let Some((col, '[')) = delim {
+                let val = match val {
+                    Some((_, ch)) if ch.is_ascii_alphabetic() => ch,
+                    _ => return Err(line.error(col + 2, "expected a crate letter")),
+                };
+
+                if !matches!(chars.next(), Some((_, ']'))) {
+                    return Err(line.error(col + 3, "expected ']'"));
+                }
+
                 while self.0.len() <= idx {
                     self.0.push(Vec::new());
                 }
 
                 self.0[idx].push(val)
+            } else {
+                chars.next();
             }
 
             chars.next();
-            chars.next();
         }
+
+        Ok(())
     }
 
-    fn move_one_at_a_time(&mut self, m: Move) {
+    fn heights(&self) -> Vec<usize> {
+        self.0.iter().map(Vec::len).collect()
+    }
+
+    // `heights` holds the number of crates in each stack after the moves checked so far
+    fn check(m: &Move, heights: &mut [usize]) -> Result<()> {
+        let n = heights.len().saturating_sub(1);
+        if let Some(s) = [m.from, m.to].into_iter().find(|&s| s == 0 || s > n) {
+            return Err(Error::parse(
+                1,
+                format!("no stack {s}, expected 1 through {n}"),
+            ));
+        }
+
+        if heights[m.from] < m.n {
+            let reason = format!("stack {} only holds {} crates", m.from, heights[m.from]);
+            return Err(Error::parse(1, reason));
+        }
+        heights[m.from] -= m.n;
+        heights[m.to] += m.n;
+        Ok(())
+    }
+
+    pub fn move_one_at_a_time(&mut self, m: Move) {
         for _ in 0..m.n {
             let v = self.0[m.from].pop().unwrap();
             self.0[m.to].push(v);
         }
     }
 
-    fn move_n_at_a_time(&mut self, m: Move) {
+    pub fn move_n_at_a_time(&mut self, m: Move) {
         self.move_one_at_a_time(Move {
             from: m.from,
             to: 0,
@@ -66,84 +132,220 @@ impl Stack {
         })
     }
 
-    fn top_crates(&self) -> String {
-        self.0
-            .iter()
-            .skip(1)
-            .map(|v| v.last().copied())
-            .map(Option::unwrap)
-            .collect()
+    // stacks left empty have nothing on top and are skipped
+    pub fn top_crates(&self) -> String {
+        self.0.iter().skip(1).filter_map(|v| v.last()).collect()
     }
 }
 
-struct Move {
-    from: usize,
-    to: usize,
-    n: usize,
+#[derive(Copy, Clone)]
+pub struct Move {
+    pub from: usize,
+    pub to: usize,
+    pub n: usize,
 }
 
-impl From<String> for Move {
-    fn from(value: String) -> Self {
-        let mut vals = value
-            .split_whitespace()
-            .skip(1)
-            .step_by(2)
-            .map(must_parse::<usize>);
+impl FromStr for Move {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let words: Vec<&str> = value.split_whitespace().collect();
+        let [kw_move, n, kw_from, from, kw_to, to] = words[..] else {
+            return Err(Error::parse(1, "expected \"move N from A to B\""));
+        };
 
-        let n = vals.next().unwrap();
-        let from = vals.next().unwrap();
-        let to = vals.next().unwrap();
-        Self { n, from, to }
+        for (kw, expected) in [(kw_move, "move"), (kw_from, "from"), (kw_to, "to")] {
+            if kw != expected {
+                let reason = format!("expected {expected:?}, found {kw:?}");
+                return Err(Error::parse(col_of(value, kw), reason));
+            }
+        }
+
+        Ok(Self {
+            n: parse_field(value, n)?,
+            from: parse_field(value, from)?,
+            to: parse_field(value, to)?,
+        })
     }
 }
 
-fn solve_1(input: &str) -> String {
-    let mut lines = file_lines(input);
-    let mut stacks = Stack::new(&mut lines);
+pub struct Dec05;
+
+impl Solution for Dec05 {
+    type Input = (Stack, Vec<Move>);
+    type Output1 = String;
+    type Output2 = String;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        let mut lines = input.lines()?;
+        let stacks = Stack::new(&mut lines)?;
 
-    for m in lines.map(Move::from) {
-        stacks.move_one_at_a_time(m);
+        let mut heights = stacks.heights();
+        let moves = lines
+            .map(|l| {
+                let m = l.parse::<Move>()?;
+                Stack::check(&m, &mut heights).map_err(|e| e.at_line(l.no))?;
+                Ok(m)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok((stacks, moves))
     }
 
-    stacks.top_crates()
-}
+    fn part_1((stacks, moves): &Self::Input) -> Self::Output1 {
+        let mut stacks = stacks.clone();
 
-fn solve_2(input: &str) -> String {
-    let mut lines = file_lines(input);
-    let mut stacks = Stack::new(&mut lines);
+        for &m in moves {
+            stacks.move_one_at_a_time(m);
+        }
 
-    for m in lines.map(Move::from) {
-        stacks.move_n_at_a_time(m);
+        stacks.top_crates()
     }
 
-    stacks.top_crates()
+    fn part_2((stacks, moves): &Self::Input) -> Self::Output2 {
+        let mut stacks = stacks.clone();
+
+        for &m in moves {
+            stacks.move_n_at_a_time(m);
+        }
+
+        stacks.top_crates()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, pick, vecs};
 
     #[test]
-    fn example_1() {
-        let input = "src/dec05/example_1.txt";
-        assert_eq!("CMZ", solve_1(input));
+    fn parse_error() {
+        let err = "move 1 frm 2 to 1".parse::<Move>().err().unwrap();
+        assert_eq!(
+            "column 8: expected \"from\", found \"frm\"",
+            err.to_string()
+        );
+
+        // the labels say how many stacks there are, even if the last ones start out empty
+        let input = "[A] [B]\n 1   2   3\n\nmove 1 from 1 to 3\nmove 1 from 2 to 4\n";
+        let err = Dec05::parse(input.into()).err().unwrap();
+        assert_eq!(
+            "line 5, column 1: no stack 4, expected 1 through 3",
+            err.to_string()
+        );
+
+        let input = "[A]\n[B] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 2 from 2 to 1\n";
+        let err = Dec05::parse(input.into()).err().unwrap();
+        assert_eq!(
+            "line 6, column 1: stack 2 only holds 0 crates",
+            err.to_string()
+        );
+
+        let err = Dec05::parse("[A] [B]\n\nmove 1 from 1 to 2\n".into()).err().unwrap();
+        assert_eq!(
+            "line 2, column 1: expected a line of stack numbers",
+            err.to_string()
+        );
+        let err = Dec05::parse("[A] [B]\n 1\n".into()).err().unwrap();
+        assert_eq!("line 2, column 3: expected 2 stacks, found 1", err.to_string());
+        let err = Dec05::parse("[A] [B]\n 1   3\n".into()).err().unwrap();
+        assert_eq!("line 2, column 6: expected stack 2", err.to_string());
     }
 
     #[test]
-    fn puzzle_1() {
-        let input = "src/dec05/input_1.txt";
-        assert_eq!("JRVNHHCSJ", solve_1(input));
+    fn empty_stacks() {
+        let input = "[A]\n[B] [C]\n 1   2   3\n\nmove 1 from 2 to 1\n";
+        let input = Dec05::parse(input.into()).unwrap();
+        assert_eq!("C", Dec05::part_1(&input));
+        assert_eq!("C", Dec05::part_2(&input));
     }
 
-    #[test]
-    fn example_2() {
-        let input = "src/dec05/example_1.txt";
-        assert_eq!("MCD", solve_2(input));
+    // stacks bottom to top, and moves as (n, from, to) counted from 1
+    type Plan = (Vec<Vec<char>>, Vec<(usize, usize, usize)>);
+
+    // Turns arbitrary numbers into moves that never take more crates than a stack holds, except
+    // for an `over` move, which takes one crate too many and ends the plan with the error parsing
+    // it should give.
+    fn plan(stacks: &[Vec<char>], raw: &[(usize, usize, usize, bool)]) -> (Plan, Option<String>) {
+        let mut sizes: Vec<usize> = stacks.iter().map(Vec::len).collect();
+        let mut moves = Vec::new();
+        for &(n, from, to, over) in raw {
+            let (from, to) = (from % sizes.len(), to % sizes.len());
+            if over {
+                moves.push((sizes[from] + 1, from + 1, to + 1));
+                let error = format!("stack {} only holds {} crates", from + 1, sizes[from]);
+                return ((stacks.to_vec(), moves), Some(error));
+            }
+
+            let n = n.min(sizes[from]);
+            if n == 0 {
+                continue;
+            }
+            sizes[from] -= n;
+            sizes[to] += n;
+            moves.push((n, from + 1, to + 1));
+        }
+        ((stacks.to_vec(), moves), None)
+    }
+
+    fn render((stacks, moves): &Plan) -> String {
+        let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let mut lines: Vec<String> = (0..height)
+            .rev()
+            .map(|row| {
+                let cells: Vec<String> = stacks
+                    .iter()
+                    .map(|s| s.get(row).map_or("   ".to_owned(), |c| format!("[{c}]")))
+                    .collect();
+                cells.join(" ")
+            })
+            .collect();
+        let labels: Vec<String> = (1..=stacks.len()).map(|n| format!(" {n} ")).collect();
+        lines.push(labels.join(" "));
+        lines.push(String::new());
+        lines.extend(
+            moves
+                .iter()
+                .map(|(n, from, to)| format!("move {n} from {from} to {to}")),
+        );
+        lines.join("\n")
+    }
+
+    // the top crates after every move, skipping empty stacks
+    fn naive((stacks, moves): &Plan, in_order: bool) -> String {
+        let mut stacks = stacks.clone();
+        for &(n, from, to) in moves {
+            if in_order {
+                let at = stacks[from - 1].len() - n;
+                let moved = stacks[from - 1].split_off(at);
+                stacks[to - 1].extend(moved);
+            } else {
+                for _ in 0..n {
+                    let c = stacks[from - 1].pop().unwrap();
+                    stacks[to - 1].push(c);
+                }
+            }
+        }
+        stacks.iter().filter_map(|s| s.last()).collect()
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec05/input_1.txt";
-        assert_eq!("GNFBSBJLH", solve_2(input));
+    fn matches_reference() {
+        let stacks = vecs(vecs(pick(&['A', 'B', 'X', 'Z']), 0..=5), 1..=5);
+        let over = pick(&[false, false, false, false, false, false, false, true]);
+        let moves = vecs((ints(1usize..=5), ints(0usize..=9), ints(0usize..=9), over), 0..=10);
+        check("dec05", (stacks, moves), |(stacks, moves)| {
+            let (plan, error) = plan(stacks, moves);
+            let parsed = Dec05::parse(Input::Str(&render(&plan)));
+            if let Some(error) = error {
+                let err = parsed.err().map(|e| e.to_string()).unwrap_or_default();
+                let reason = err.split_once(": ").map_or("", |(_, reason)| reason);
+                return expect_eq("error", error.as_str(), reason);
+            }
+
+            let input = parsed.map_err(|e| e.to_string())?;
+            expect_eq("part 1", naive(&plan, false), Dec05::part_1(&input))?;
+            expect_eq("part 2", naive(&plan, true), Dec05::part_2(&input))
+        });
     }
 }
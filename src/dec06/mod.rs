@@ -1,9 +1,39 @@
 use crate::utils::*;
+use crate::{Error, Result, Solution};
 use std::collections::VecDeque;
-use std::io::Read;
 
-fn find_distinct_run(input: &str, n: usize) -> usize {
-    let chars = load_file(input).bytes().map(Result::unwrap).enumerate();
+pub struct Dec06;
+
+impl Solution for Dec06 {
+    type Input = Vec<u8>;
+    type Output1 = Result<usize>;
+    type Output2 = Result<usize>;
+
+    // a single line of letters, so the newline at its end can't count towards a marker
+    fn parse(input: Input) -> Result<Self::Input> {
+        let mut bytes = input.bytes()?;
+        while bytes.last().is_some_and(u8::is_ascii_whitespace) {
+            bytes.pop();
+        }
+
+        if let Some(idx) = bytes.iter().position(|b| !b.is_ascii_lowercase()) {
+            let reason = format!("expected a lowercase letter, found {:?}", bytes[idx] as char);
+            return Err(Error::parse(idx + 1, reason).at_line(1));
+        }
+        Ok(bytes)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        find_distinct_run(input, 4)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        find_distinct_run(input, 14)
+    }
+}
+
+pub fn find_distinct_run(input: &[u8], n: usize) -> Result<usize> {
+    let chars = input.iter().copied().enumerate();
 
     let mut vd = VecDeque::with_capacity(n);
 
@@ -16,45 +46,55 @@ fn find_distinct_run(input: &str, n: usize) -> usize {
 
         vd.push_back(ch);
         if vd.len() == n {
-            return idx + 1;
+            return Ok(idx + 1);
         }
     }
 
-    unreachable!()
-}
-
-fn solve_1(input: &str) -> usize {
-    find_distinct_run(input, 4)
-}
-
-fn solve_2(input: &str) -> usize {
-    find_distinct_run(input, 14)
+    Err(Error::unsolvable(format!("no {n} characters in a row are distinct")))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, pick, vecs};
 
-    #[test]
-    fn example_1() {
-        assert_eq!(7, solve_1("src/dec06/example_1.txt"));
+    // checks every window against every other character in it
+    fn naive(input: &[u8], n: usize) -> Option<usize> {
+        (n..=input.len()).find(|&end| {
+            let window = &input[end - n..end];
+            (0..n).all(|i| (i + 1..n).all(|j| window[i] != window[j]))
+        })
     }
 
     #[test]
-    fn puzzle_1() {
-        let input = "src/dec06/input_1.txt";
-        assert_eq!(1723, solve_1(input));
+    fn matches_reference() {
+        let letters = pick(b"abcdefghijklmnop");
+        check("dec06", (vecs(letters, 0..=40), ints(1usize..=14)), |(bytes, n)| {
+            // always ends in a distinct run, which the puzzle guarantees
+            let mut input = bytes.clone();
+            input.extend(b"abcdefghijklmn");
+            expect_eq("run", naive(&input, *n).unwrap(), find_distinct_run(&input, *n).unwrap())
+        });
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec06/example_1.txt";
-        assert_eq!(19, solve_2(input));
+    fn parse() {
+        assert_eq!(b"abc".to_vec(), Dec06::parse("abc\r\n".into()).unwrap());
+        let input = Dec06::parse("abc\n".into()).unwrap();
+        assert!(Dec06::part_1(&input).is_err());
+
+        let err = Dec06::parse("abc\ndef\n".into()).err().unwrap();
+        assert_eq!(
+            "line 1, column 4: expected a lowercase letter, found '\\n'",
+            err.to_string()
+        );
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec06/input_1.txt";
-        assert_eq!(3708, solve_2(input));
+    fn no_run() {
+        let err = find_distinct_run(b"abc", 4).err().unwrap();
+        assert_eq!("no answer: no 4 characters in a row are distinct", err.to_string());
+        assert!(find_distinct_run(b"abcabcabc", 4).is_err());
+        assert_eq!(4, find_distinct_run(b"abcd", 4).unwrap());
     }
 }
@@ -1,4 +1,6 @@
+use crate::params::{Param, Params};
 use crate::utils::*;
+use crate::{Error, Part, Result, Solution};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -35,15 +37,9 @@ impl Dir {
 }
 
 #[derive(Debug)]
-struct File {
-    size: usize,
-    parent: Rc<RefCell<Dir>>,
-}
-
-#[derive(Debug)]
-struct FS {
+pub struct FS {
     dirs: HashMap<String, Rc<RefCell<Dir>>>,
-    files: HashMap<String, File>,
+    files: HashMap<String, usize>,
 }
 
 impl FS {
@@ -57,17 +53,40 @@ impl FS {
         fs
     }
 
-    fn add_dir(&mut self, parent: &str, name: &str) {
-        let d = Dir::new(Rc::clone(self.dirs.get(parent).unwrap()));
+    fn dir(&self, path: &str) -> Result<Rc<RefCell<Dir>>> {
+        let dir = self.dirs.get(path);
+        dir.map(Rc::clone)
+            .ok_or_else(|| Error::parse(1, format!("no such directory {path:?}")))
+    }
+
+    fn add_dir(&mut self, parent: &str, name: &str) -> Result<()> {
+        let d = Dir::new(self.dir(parent)?);
         self.dirs
             .insert(Self::full_name(parent, name), Rc::new(RefCell::new(d)));
+        Ok(())
+    }
+
+    fn add_file(&mut self, parent: &str, name: &str, size: usize) -> Result<()> {
+        self.dir(parent)?.borrow_mut().add_size(size);
+        self.files.insert(Self::full_name(parent, name), size);
+        Ok(())
     }
 
-    fn add_file(&mut self, parent: &str, name: &str, size: usize) {
-        let p = Rc::clone(self.dirs.get(parent).unwrap());
-        p.borrow_mut().add_size(size);
-        self.files
-            .insert(Self::full_name(parent, name), File { parent: p, size });
+    // names are single path components, so every directory's parent is registered before it
+    fn check_name(line: &Line, name: &str) -> Result<()> {
+        if name.contains('/') || name == "." || name == ".." {
+            return Err(line.field_error(name, format!("invalid name {name:?}")));
+        }
+        Ok(())
+    }
+
+    // total size of everything under the absolute directory `path`
+    pub fn dir_size(&self, path: &str) -> Option<usize> {
+        self.dirs.get(path).map(|d| d.borrow().size)
+    }
+
+    pub fn file_size(&self, path: &str) -> Option<usize> {
+        self.files.get(path).copied()
     }
 
     fn full_name(parent: &str, name: &str) -> String {
@@ -78,7 +97,18 @@ impl FS {
         }
     }
 
-    fn sum_of_dirs_at_most(&self, size: usize) -> usize {
+    // every directory with its total size, smallest first
+    pub fn dir_sizes(&self) -> Vec<(&str, usize)> {
+        let mut sizes: Vec<(&str, usize)> = self
+            .dirs
+            .iter()
+            .map(|(name, d)| (name.as_str(), d.borrow().size))
+            .collect();
+        sizes.sort_by_key(|&(name, size)| (size, name));
+        sizes
+    }
+
+    pub fn sum_of_dirs_at_most(&self, size: usize) -> usize {
         self.dirs
             .iter()
             .filter(|&(name, _)| name != "/")
@@ -87,8 +117,8 @@ impl FS {
             .sum()
     }
 
-    fn min_to_delete(&self, target: usize) -> usize {
-        let at_least = self.dirs.get("/").unwrap().borrow().size - target;
+    pub fn min_to_delete(&self, target: usize) -> usize {
+        let at_least = self.dirs.get("/").unwrap().borrow().size.saturating_sub(target);
         self.dirs
             .values()
             .map(|d| d.borrow().size)
@@ -98,79 +128,257 @@ impl FS {
     }
 }
 
-impl FromIterator<String> for FS {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
+impl FromLines for FS {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
         let mut fs = FS::new();
-        let mut current = PathBuf::new();
+        let mut current = PathBuf::from("/");
 
-        let mut i = iter.into_iter().peekable();
+        let mut i = lines.peekable();
         while let Some(line) = i.next() {
-            let mut parts = line.split_whitespace().skip(1);
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("$") => {}
+                _ => return Err(line.error(1, "expected a command")),
+            }
 
-            match parts.next().unwrap() {
-                "cd" => match parts.next().unwrap() {
-                    ".." => {
+            match parts.next() {
+                Some("cd") => match parts.next() {
+                    Some("..") => {
                         current.pop();
+                        if !fs.dirs.contains_key(current.to_str().unwrap()) {
+                            let reason = format!("no such directory {:?}", current.display());
+                            return Err(line.error(1, reason));
+                        }
                     }
-                    d => {
-                        current.push(d);
+                    Some(d) => {
+                        if d != "/" {
+                            FS::check_name(&line, d)?;
+                        }
+                        let next = current.join(d);
+                        if !fs.dirs.contains_key(next.to_str().unwrap()) {
+                            return Err(line.field_error(d, format!("no such directory {d:?}")));
+                        }
+                        current = next;
                     }
+                    None => return Err(line.error(line.len() + 1, "expected a directory")),
                 },
-                "ls" => {
-                    while !i.peek().map_or(true, |l| l.starts_with('$')) {
-                        let sl: String = i.next().unwrap();
+                Some("ls") => {
+                    while i.peek().is_some_and(|l| !l.starts_with('$')) {
+                        let sl = i.next().unwrap();
                         let mut parts = sl.split_whitespace();
                         let parent = current.to_str().unwrap();
 
-                        match parts.next().unwrap() {
-                            "dir" => fs.add_dir(parent, parts.next().unwrap()),
-                            s => fs.add_file(parent, parts.next().unwrap(), must_parse(s)),
+                        let (kind, name) = match (parts.next(), parts.next()) {
+                            (Some(kind), Some(name)) => (kind, name),
+                            _ => return Err(sl.error(1, "expected \"dir NAME\" or \"SIZE NAME\"")),
+                        };
+
+                        FS::check_name(&sl, name)?;
+                        match kind {
+                            "dir" => fs.add_dir(parent, name).map_err(|e| e.at_line(sl.no))?,
+                            s => {
+                                let size = sl.parse_field(s)?;
+                                // the root holds everything, so it overflows first
+                                if fs.dir_size("/").unwrap().checked_add(size).is_none() {
+                                    return Err(sl.field_error(s, "total size overflows"));
+                                }
+                                fs.add_file(parent, name, size).map_err(|e| e.at_line(sl.no))?
+                            }
                         }
                     }
                 }
-                _ => unreachable!(),
+                Some(cmd) => return Err(line.field_error(cmd, format!("unknown command {cmd:?}"))),
+                None => return Err(line.error(line.len() + 1, "expected a command")),
             }
         }
 
-        fs
+        Ok(fs)
     }
 }
 
-fn solve_1(input: &str) -> usize {
-    let fs = FS::from_iter(file_lines(input));
-    fs.sum_of_dirs_at_most(100_000)
-}
+pub struct Dec07;
+
+impl Solution for Dec07 {
+    type Input = FS;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const PARAMS: &'static [Param] = &[
+        Param::int("small", 100_000, "largest directory part 1 counts"),
+        Param::int("disk", 70_000_000, "size of the disk"),
+        Param::int("needed", 30_000_000, "free space the update needs"),
+    ];
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        FS::from_lines(input.lines()?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        Self::part_1_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        Self::part_2_with(input, &Params::defaults(Self::PARAMS))
+    }
 
-fn solve_2(input: &str) -> usize {
-    let fs = FS::from_iter(file_lines(input));
-    fs.min_to_delete(40_000_000)
+    fn part_1_with(input: &Self::Input, params: &Params) -> Self::Output1 {
+        input.sum_of_dirs_at_most(params.int("small"))
+    }
+
+    // deletes enough to leave no more than `disk - needed` in use
+    fn part_2_with(input: &Self::Input, params: &Params) -> Self::Output2 {
+        let target = params.int("disk").saturating_sub(params.int("needed"));
+        input.min_to_delete(target)
+    }
+
+    // the directories each part picks from, and their sizes
+    fn explain(input: &Self::Input, part: Part, params: &Params) -> Option<String> {
+        let sizes = input.dir_sizes();
+        let mut out = Vec::new();
+        match part {
+            Part::One => {
+                let small = params.int("small");
+                let dirs: Vec<_> = sizes.iter().filter(|&&(n, s)| n != "/" && s <= small).collect();
+                out.push(format!("{} directories of at most {small}:", dirs.len()));
+                out.extend(dirs.iter().map(|(name, size)| format!("  {name}: {size}")));
+            }
+            Part::Two => {
+                let used = input.dir_size("/").unwrap();
+                let target = params.int("disk").saturating_sub(params.int("needed"));
+                let at_least = used.saturating_sub(target);
+                out.push(format!(
+                    "{used} used, at most {target} may be, so at least {at_least} must go:"
+                ));
+                let dirs = sizes.iter().filter(|&&(_, s)| s >= at_least);
+                for (idx, (name, size)) in dirs.enumerate() {
+                    let pick = if idx == 0 { " <- smallest" } else { "" };
+                    out.push(format!("  {name}: {size}{pick}"));
+                }
+            }
+        }
+        Some(out.join("\n"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, vecs};
+    use std::collections::{BTreeMap, BTreeSet};
 
-    #[test]
-    fn example_1() {
-        let input = "src/dec07/example_1.txt";
-        assert_eq!(95437, solve_1(input));
+    // files keyed by the path of their directory, as indexes into `d0`, `d1`..., and their name
+    type Files = BTreeMap<(Vec<usize>, usize), usize>;
+
+    fn path(dir: &[usize]) -> String {
+        let names: Vec<String> = dir.iter().map(|d| format!("/d{d}")).collect();
+        if names.is_empty() {
+            "/".to_owned()
+        } else {
+            names.concat()
+        }
     }
 
-    #[test]
-    fn puzzle_1() {
-        let input = "src/dec07/input_1.txt";
-        assert_eq!(1723892, solve_1(input));
+    // every directory holding a file, and all of their parents
+    fn dirs(files: &Files) -> BTreeSet<Vec<usize>> {
+        files
+            .keys()
+            .flat_map(|(dir, _)| (0..=dir.len()).map(|n| dir[..n].to_vec()))
+            .collect()
+    }
+
+    // a terminal session that lists every directory, depth first
+    fn transcript(files: &Files) -> String {
+        fn visit(dir: &[usize], files: &Files, dirs: &BTreeSet<Vec<usize>>, out: &mut Vec<String>) {
+            let children: Vec<&Vec<usize>> = dirs
+                .iter()
+                .filter(|d| d.len() == dir.len() + 1 && d.starts_with(dir))
+                .collect();
+
+            out.push("$ ls".to_owned());
+            for child in &children {
+                out.push(format!("dir d{}", child[dir.len()]));
+            }
+            for ((_, name), size) in files.iter().filter(|((d, _), _)| d == dir) {
+                out.push(format!("{size} f{name}"));
+            }
+            for child in children {
+                out.push(format!("$ cd d{}", child[dir.len()]));
+                visit(child, files, dirs, out);
+                out.push("$ cd ..".to_owned());
+            }
+        }
+
+        let mut out = vec!["$ cd /".to_owned()];
+        visit(&[], files, &dirs(files), &mut out);
+        out.join("\n")
+    }
+
+    // sums every file under `dir`
+    fn naive_size(files: &Files, dir: &[usize]) -> usize {
+        files
+            .iter()
+            .filter(|((d, _), _)| d.starts_with(dir))
+            .map(|(_, size)| size)
+            .sum()
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec07/example_1.txt";
-        assert_eq!(24933642, solve_2(input));
+    fn names() {
+        let err = |src: &str| Dec07::parse(src.into()).err().unwrap().to_string();
+        assert_eq!(
+            "line 3, column 5: invalid name \"a/b\"",
+            err("$ cd /\n$ ls\ndir a/b\n$ cd a/b\n$ cd ..\n$ ls\n1 f\n")
+        );
+        assert_eq!(
+            "line 2, column 6: invalid name \"a/b\"",
+            err("$ cd /\n$ cd a/b\n")
+        );
+        assert_eq!("line 3, column 3: invalid name \"..\"", err("$ cd /\n$ ls\n1 ..\n"));
+        assert_eq!("line 2, column 6: invalid name \".\"", err("$ cd /\n$ cd .\n"));
+
+        let fs = Dec07::parse("$ cd /\n$ ls\ndir a\n$ cd a\n$ cd ..\n$ ls\n1 f\n".into()).unwrap();
+        assert_eq!(Some(1), fs.file_size("/f"));
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec07/input_1.txt";
-        assert_eq!(8474158, solve_2(input));
+    fn matches_reference() {
+        let file = (vecs(ints(0usize..=2), 0..=3), ints(0usize..=3), ints(1usize..=150_000));
+        check("dec07", (vecs(file, 0..=12), ints(0usize..=100)), |(entries, pct)| {
+            let files: Files = entries
+                .iter()
+                .map(|(dir, name, size)| ((dir.clone(), *name), *size))
+                .collect();
+            let fs = FS::from_lines(Input::Str(&transcript(&files)).lines().unwrap())
+                .map_err(|e| e.to_string())?;
+
+            let sizes: Vec<(Vec<usize>, usize)> = dirs(&files)
+                .into_iter()
+                .chain([vec![]])
+                .map(|d| {
+                    let size = naive_size(&files, &d);
+                    (d, size)
+                })
+                .collect();
+            for (dir, size) in &sizes {
+                expect_eq(&path(dir), Some(*size), fs.dir_size(&path(dir)))?;
+            }
+
+            let at_most = sizes
+                .iter()
+                .filter(|(d, s)| !d.is_empty() && *s <= 100_000)
+                .map(|(_, s)| s)
+                .sum();
+            expect_eq("sum of dirs", at_most, fs.sum_of_dirs_at_most(100_000))?;
+
+            let total = naive_size(&files, &[]);
+            let target = total * pct / 100;
+            let smallest = sizes
+                .iter()
+                .map(|&(_, s)| s)
+                .filter(|&s| s >= total - target)
+                .min()
+                .unwrap();
+            expect_eq("min to delete", smallest, fs.min_to_delete(target))
+        });
     }
 }
@@ -1,7 +1,8 @@
 use crate::utils::*;
+use crate::{Error, Result, Solution};
 
-#[derive(Debug)]
-struct Grid {
+#[derive(Debug, Clone)]
+pub struct Grid {
     heights: Vec<Vec<i8>>,
     visible: Vec<Vec<bool>>,
     rows: usize,
@@ -54,11 +55,12 @@ impl Grid {
         r >= 0 && r < self.rows as isize && c >= 0 && c < self.cols as isize
     }
 
-    fn total_visible(&self) -> usize {
+    pub fn total_visible(&self) -> usize {
         self.visible.iter().flatten().filter(|&&b| b).count()
     }
 
-    fn scenic_score(&self, r: isize, c: isize) -> u32 {
+    pub fn scenic_score(&self, r: usize, c: usize) -> u32 {
+        let (r, c) = (r as isize, c as isize);
         self.score_helper(r, c, -1, 0)
             * self.score_helper(r, c, 0, -1)
             * self.score_helper(r, c, 0, 1)
@@ -83,75 +85,128 @@ impl Grid {
         ct
     }
 
-    fn best_score(&self) -> u32 {
+    // trees on the edge see nothing in one direction, so score 0, as does a grid with no interior
+    pub fn best_score(&self) -> u32 {
         (1..(self.rows - 1))
             .flat_map(|r| (1..(self.cols - 1)).map(move |c| (r, c)))
-            .map(|(r, c)| self.scenic_score(r as isize, c as isize))
+            .map(|(r, c)| self.scenic_score(r, c))
             .max()
-            .unwrap()
+            .unwrap_or(0)
     }
 }
 
-impl FromIterator<String> for Grid {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let mut heights = Vec::new();
-
-        for line in iter.into_iter() {
-            let row: Vec<i8> = line
-                .chars()
-                .map(|ch: char| ch.to_digit(10).unwrap() as i8)
-                .collect();
+impl FromLines for Grid {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
+        let mut heights: Vec<Vec<i8>> = Vec::new();
+
+        for line in lines {
+            let row = line
+                .char_indices()
+                .map(|(idx, ch)| match ch.to_digit(10) {
+                    Some(d) => Ok(d as i8),
+                    None => Err(line.error(idx + 1, format!("expected a height, found {ch:?}"))),
+                })
+                .collect::<Result<Vec<i8>>>()?;
+
+            let cols = heights.first().map_or(row.len(), Vec::len);
+            if row.len() != cols {
+                let reason = format!("expected {cols} trees, found {}", row.len());
+                return Err(line.error(1, reason));
+            }
             heights.push(row);
         }
 
         let rows = heights.len();
-        let cols = heights[0].len();
+        let cols = heights.first().map_or(0, Vec::len);
+        if cols == 0 {
+            return Err(Error::parse(1, "empty grid").at_line(1));
+        }
+
         let visible = vec![vec![false; cols]; rows];
-        Self {
+        Ok(Self {
             heights,
             visible,
             rows,
             cols,
-        }
+        })
     }
 }
 
-fn solve_1(input: &str) -> usize {
-    let mut grid = Grid::from_iter(file_lines(input));
-    grid.scan();
-    grid.total_visible()
-}
+pub struct Dec08;
+
+impl Solution for Dec08 {
+    type Input = Grid;
+    type Output1 = usize;
+    type Output2 = u32;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        Grid::from_lines(input.lines()?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        let mut grid = input.clone();
+        grid.scan();
+        grid.total_visible()
+    }
 
-fn solve_2(input: &str) -> u32 {
-    let grid = Grid::from_iter(file_lines(input));
-    grid.best_score()
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        input.best_score()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, vecs};
 
-    #[test]
-    fn example_1() {
-        let input = "src/dec08/example_1.txt";
-        assert_eq!(21, solve_1(input));
-    }
+    const DIRS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
 
-    #[test]
-    fn puzzle_1() {
-        let input = "src/dec08/input_1.txt";
-        assert_eq!(1_870, solve_1(input));
+    // heights along the line of sight from (r, c), nearest first
+    fn sight(heights: &[Vec<u8>], r: usize, c: usize, (dr, dc): (isize, isize)) -> Vec<u8> {
+        let (mut r, mut c) = (r as isize + dr, c as isize + dc);
+        let mut out = Vec::new();
+        while let Some(&h) = heights.get(r as usize).and_then(|row| row.get(c as usize)) {
+            out.push(h);
+            (r, c) = (r + dr, c + dc);
+        }
+        out
     }
 
-    #[test]
-    fn example_2() {
-        let input = "src/dec08/example_1.txt";
-        assert_eq!(8, solve_2(input));
+    fn naive(heights: &[Vec<u8>]) -> (usize, u32) {
+        let (mut visible, mut best) = (0, 0);
+        for (r, row) in heights.iter().enumerate() {
+            for (c, &h) in row.iter().enumerate() {
+                let lines = DIRS.map(|d| sight(heights, r, c, d));
+                if lines.iter().any(|l| l.iter().all(|&t| t < h)) {
+                    visible += 1;
+                }
+
+                let score = lines
+                    .iter()
+                    .map(|l| l.iter().position(|&t| t >= h).map_or(l.len(), |i| i + 1) as u32)
+                    .product();
+                best = best.max(score);
+            }
+        }
+        (visible, best)
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec08/input_1.txt";
-        assert_eq!(517_440, solve_2(input));
+    fn matches_reference() {
+        let grid = (ints(1usize..=7), ints(1usize..=7), vecs(ints(0u8..=9), 49..=49));
+        check("dec08", grid, |(rows, cols, digits)| {
+            let heights: Vec<Vec<u8>> =
+                digits.chunks(*cols).take(*rows).map(<[u8]>::to_vec).collect();
+            let text: Vec<String> = heights
+                .iter()
+                .map(|row| row.iter().map(u8::to_string).collect())
+                .collect();
+            let input = Dec08::parse(Input::Str(&text.join("\n"))).map_err(|e| e.to_string())?;
+
+            let (visible, best) = naive(&heights);
+            expect_eq("part 1", visible, Dec08::part_1(&input))?;
+            // trees on the edge score 0, so grids narrower than 3 have a best score of 0 too
+            expect_eq("part 2", best, Dec08::part_2(&input))
+        });
     }
 }
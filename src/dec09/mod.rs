@@ -1,24 +1,34 @@
+use crate::checkpoint::{Checkpoint, Records, Writer};
+use crate::params::{Param, Params};
+use crate::trace::event;
 use crate::utils::*;
+use crate::{Error, Result, Solution};
 use std::collections::HashSet;
-use std::iter::repeat;
+use std::iter::repeat_n;
+use std::str::FromStr;
 
-#[derive(Copy, Clone)]
-enum Dir {
+#[derive(Copy, Clone, Debug)]
+pub enum Dir {
     Up,
     Down,
     Left,
     Right,
 }
 
-impl From<&str> for Dir {
-    fn from(value: &str) -> Self {
+impl FromStr for Dir {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
         use Dir::*;
         match value {
-            "U" => Up,
-            "D" => Down,
-            "L" => Left,
-            "R" => Right,
-            _ => unreachable!(),
+            "U" => Ok(Up),
+            "D" => Ok(Down),
+            "L" => Ok(Left),
+            "R" => Ok(Right),
+            _ => Err(Error::parse(
+                1,
+                format!("expected U, D, L or R, found {value:?}"),
+            )),
         }
     }
 }
@@ -63,20 +73,26 @@ impl Knot {
     }
 }
 
-struct Chain {
+pub struct Chain {
     knots: Vec<Knot>,
     history: HashSet<(isize, isize)>,
+    // taken so far
+    steps: usize,
 }
 
 impl Chain {
-    fn new(n: usize) -> Self {
+    pub fn new(n: usize) -> Self {
         let knots = (0..n).map(|_| Knot::default()).collect();
         let mut history = HashSet::new();
         history.insert((0, 0));
-        Self { knots, history }
+        Self {
+            knots,
+            history,
+            steps: 0,
+        }
     }
 
-    fn step(&mut self, d: Dir) {
+    pub fn step(&mut self, d: Dir) {
         self.knots[0].step_towards(d);
         let (mut x, mut y) = (self.knots[0].tx, self.knots[0].ty);
 
@@ -87,58 +103,216 @@ impl Chain {
             }
         }
 
+        let head = &self.knots[0];
+        event!(
+            "step",
+            dir = format_args!("{d:?}"),
+            head = format_args!("{},{}", head.hx, head.hy),
+            tail = format_args!("{x},{y}")
+        );
         self.history.insert((x, y));
+        self.steps += 1;
     }
 
-    fn read_path<I: Iterator<Item = String>>(&mut self, iter: I) {
-        iter.into_iter()
-            .flat_map(|s: String| {
-                let (d, n) = s.split_once(' ').unwrap();
-                let dir = Dir::from(d);
-                let ct = must_parse(n);
-                repeat(dir).take(ct)
-            })
-            .for_each(|d| self.step(d));
+    // follows `path` from however many steps of it the chain has already taken
+    pub fn read_path(&mut self, path: &[(Dir, usize)]) {
+        self.read_steps(path, usize::MAX);
+    }
+
+    // like `read_path`, stopping after `limit` steps; returns whether the path is finished
+    pub fn read_steps(&mut self, path: &[(Dir, usize)], limit: usize) -> bool {
+        let mut rest = path
+            .iter()
+            .flat_map(|&(dir, ct)| repeat_n(dir, ct))
+            .skip(self.steps);
+        rest.by_ref().take(limit).for_each(|d| self.step(d));
+        rest.next().is_none()
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    // number of distinct positions the tail has occupied
+    pub fn visited(&self) -> usize {
+        self.history.len()
+    }
+}
+
+// The steps taken, every knot's head and tail, and the positions the last tail has visited:
+//
+//   steps <n>
+//   knot <head x> <head y> <tail x> <tail y>
+//   visited <x> <y>
+impl Checkpoint for Chain {
+    const KIND: &'static str = "dec09";
+
+    fn save(&self, out: &mut Writer) {
+        out.record("steps", &[&self.steps]);
+        for k in &self.knots {
+            out.record("knot", &[&k.hx, &k.hy, &k.tx, &k.ty]);
+        }
+        let mut visited: Vec<_> = self.history.iter().collect();
+        visited.sort();
+        for (x, y) in visited {
+            out.record("visited", &[x, y]);
+        }
+    }
+
+    fn load(records: &mut Records) -> Result<Self> {
+        let steps = records.next("steps")?.field(0)?;
+
+        let mut knots: Vec<Knot> = Vec::new();
+        for r in records.all("knot") {
+            let knot = Knot {
+                hx: r.field(0)?,
+                hy: r.field(1)?,
+                tx: r.field(2)?,
+                ty: r.field(3)?,
+            };
+            // each knot's head is the tail of the one before
+            if let Some(prev) = knots.last() {
+                if (knot.hx, knot.hy) != (prev.tx, prev.ty) {
+                    return Err(r.error(0, "head is not on the previous tail"));
+                }
+            }
+            if knot.hx.abs_diff(knot.tx) > 1 || knot.hy.abs_diff(knot.ty) > 1 {
+                return Err(r.error(2, "tail is not touching the head"));
+            }
+            knots.push(knot);
+        }
+        if knots.is_empty() {
+            // reports the record that should have been a knot
+            records.next("knot")?;
+        }
+
+        let history = records
+            .all("visited")
+            .iter()
+            .map(|r| Ok((r.field(0)?, r.field(1)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            knots,
+            history,
+            steps,
+        })
     }
 }
 
-fn solve_1(input: &str) -> usize {
-    let mut chain = Chain::new(1);
-    chain.read_path(file_lines(input));
-    chain.history.len()
+pub struct Dec09;
+
+impl Solution for Dec09 {
+    type Input = Vec<(Dir, usize)>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const PARAMS: &'static [Param] = &[
+        Param::int("knots_1", 2, "knots in the part 1 rope").at_least(2),
+        Param::int("knots_2", 10, "knots in the part 2 rope").at_least(2),
+    ];
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        input
+            .lines()?
+            .map(|l| {
+                let (d, n) = l
+                    .split_once(' ')
+                    .ok_or_else(|| l.error(l.len() + 1, "expected a direction and a distance"))?;
+                Ok((l.parse_field(d)?, l.parse_field(n)?))
+            })
+            .collect()
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        Self::part_1_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        Self::part_2_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn part_1_with(input: &Self::Input, params: &Params) -> Self::Output1 {
+        rope(input, params.int("knots_1"))
+    }
+
+    fn part_2_with(input: &Self::Input, params: &Params) -> Self::Output2 {
+        rope(input, params.int("knots_2"))
+    }
 }
 
-fn solve_2(input: &str) -> usize {
-    let mut chain = Chain::new(9);
-    chain.read_path(file_lines(input));
-    chain.history.len()
+// tail positions visited by a rope of `knots` knots, head included
+fn rope(path: &[(Dir, usize)], knots: usize) -> usize {
+    let mut chain = Chain::new(knots - 1);
+    chain.read_path(path);
+    chain.visited()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checkpoint;
+    use crate::prop::{check, expect_eq, ints, pick, vecs};
 
     #[test]
-    fn example_1() {
-        let input = "src/dec09/example_1.txt";
-        assert_eq!(13, solve_1(input));
-    }
+    fn resumes() {
+        let path = Dec09::parse(include_str!("examples/2.txt").into()).unwrap();
+        let mut chain = Chain::new(9);
+        assert!(!chain.read_steps(&path, 41));
+        let saved = checkpoint::save(&chain);
+        assert_eq!(include_str!("checkpoints/example-2-step-41.txt"), saved);
 
-    #[test]
-    fn puzzle_1() {
-        let input = "src/dec09/input_1.txt";
-        assert_eq!(6269, solve_1(input));
+        let mut chain: Chain = checkpoint::load(saved.as_str().into()).unwrap();
+        assert_eq!((41, 8), (chain.steps(), chain.visited()));
+        assert!(chain.read_steps(&path, usize::MAX));
+        assert_eq!(36, chain.visited());
+
+        let broken = saved.replacen("knot 13 5", "knot 13 6", 1);
+        let err = checkpoint::load::<Chain>(broken.as_str().into()).err().unwrap();
+        assert_eq!("<input>:4:6: head is not on the previous tail", err.to_string());
     }
 
-    #[test]
-    fn example_2() {
-        assert_eq!(1, solve_2("src/dec09/example_1.txt"));
-        assert_eq!(36, solve_2("src/dec09/example_2.txt"));
+    // moves a whole rope of `knots` one step at a time, each knot chasing the one before it
+    fn naive(moves: &[(char, usize)], knots: usize) -> usize {
+        let mut rope = vec![(0i32, 0i32); knots];
+        let mut visited = HashSet::from([(0, 0)]);
+        for &(dir, n) in moves {
+            let (dx, dy) = match dir {
+                'U' => (0, 1),
+                'D' => (0, -1),
+                'L' => (-1, 0),
+                _ => (1, 0),
+            };
+            for _ in 0..n {
+                rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+                for k in 1..knots {
+                    let (ax, ay) = rope[k - 1];
+                    let (bx, by) = rope[k];
+                    if (ax - bx).abs() > 1 || (ay - by).abs() > 1 {
+                        rope[k] = (bx + (ax - bx).signum(), by + (ay - by).signum());
+                    }
+                }
+                visited.insert(rope[knots - 1]);
+            }
+        }
+        visited.len()
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec09/input_1.txt";
-        assert_eq!(2557, solve_2(input));
+    fn matches_reference() {
+        let moves = vecs((pick(&['U', 'D', 'L', 'R']), ints(1usize..=5)), 0..=25);
+        check("dec09", (moves, ints(1usize..=9)), |(moves, n)| {
+            let text: Vec<String> = moves.iter().map(|(d, n)| format!("{d} {n}")).collect();
+            let input = Dec09::parse(Input::Str(&text.join("\n"))).map_err(|e| e.to_string())?;
+
+            let mut chain = Chain::new(*n);
+            chain.read_path(&input);
+            // a chain of n knots tracks n + 1 positions: each knot holds a head and a tail
+            expect_eq("visited", naive(moves, n + 1), chain.visited())?;
+            expect_eq("part 1", naive(moves, 2), Dec09::part_1(&input))?;
+
+            let knots = [("knots_2".to_owned(), (n + 1).to_string())];
+            let params = Params::with(Dec09::PARAMS, &knots)?;
+            expect_eq("part 2", naive(moves, n + 1), Dec09::part_2_with(&input, &params))
+        });
     }
 }
@@ -1,7 +1,32 @@
+use crate::params::{Param, Params};
+use crate::trace::event;
 use crate::utils::*;
+use crate::{Error, Result, Solution};
 use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
 
-struct Cpu {
+#[derive(Copy, Clone)]
+pub enum Inst {
+    Noop,
+    Addx(isize),
+}
+
+impl FromStr for Inst {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.split_once(' ') {
+            None if value == "noop" => Ok(Inst::Noop),
+            Some(("addx", n)) => Ok(Inst::Addx(parse_field(value, n)?)),
+            _ => Err(Error::parse(
+                1,
+                format!("expected noop or addx, found {value:?}"),
+            )),
+        }
+    }
+}
+
+pub struct Cpu {
     cycle: isize,
     x: isize,
     capture_points: VecDeque<isize>,
@@ -21,30 +46,38 @@ impl Cpu {
         }
     }
 
-    fn read_inst(&mut self, inst: &str) {
-        match inst.split_once(' ') {
-            None if inst == "noop" => self.step(),
-            Some((op, n)) if op == "addx" => {
+    pub fn exec(&mut self, inst: Inst) {
+        match inst {
+            Inst::Noop => self.step(),
+            Inst::Addx(d) => {
                 self.step();
                 self.step();
-                let d: isize = must_parse(n);
                 self.x += d;
             }
-            _ => unreachable!(),
         }
     }
 
     fn step(&mut self) {
+        event!("cycle", n = self.cycle, x = self.x);
         self.draw();
 
-        if let Some(&pt) = self.capture_points.front() && pt == self.cycle {
+        if self.capture_points.front() == Some(&self.cycle) {
             self.capture_points.pop_front();
-            self.captures.insert(pt, self.x);
+            self.captures.insert(self.cycle, self.x);
         }
 
         self.cycle += 1;
     }
 
+    // sum of cycle * X at each capture point
+    pub fn signal_strength(&self) -> isize {
+        self.captures.iter().map(|(c, x)| *c * *x).sum()
+    }
+
+    pub fn crt(&self) -> &str {
+        &self.crt
+    }
+
     fn draw(&mut self) {
         let pos = (self.cycle - 1) % 40;
         if pos == 0 && self.cycle != 1 {
@@ -59,63 +92,113 @@ impl Cpu {
     }
 }
 
-impl FromIterator<String> for Cpu {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let capture_points = VecDeque::from_iter::<[isize; 6]>([20, 60, 100, 140, 180, 220]);
-        let mut cpu = Cpu::new(capture_points);
-        iter.into_iter().for_each(|l| cpu.read_inst(&l));
+const CAPTURES: [isize; 6] = [20, 60, 100, 140, 180, 220];
+
+impl Cpu {
+    // runs `program`, capturing X during each of the `captures` cycles
+    pub fn run<T: IntoIterator<Item = Inst>>(program: T, captures: &[isize]) -> Self {
+        let mut captures = captures.to_vec();
+        captures.sort_unstable();
+        captures.dedup();
+
+        let mut cpu = Cpu::new(captures.into());
+        program.into_iter().for_each(|i| cpu.exec(i));
         cpu
     }
 }
 
-fn solve_1(input: &str) -> isize {
-    let cpu = Cpu::from_iter(file_lines(input));
-    cpu.captures.iter().map(|(c, x)| *c * *x).sum()
+impl FromIterator<Inst> for Cpu {
+    fn from_iter<T: IntoIterator<Item = Inst>>(iter: T) -> Self {
+        Self::run(iter, &CAPTURES)
+    }
 }
 
-fn solve_2(input: &str) -> String {
-    let cpu = Cpu::from_iter(file_lines(input));
-    cpu.crt
+pub struct Dec10;
+
+impl Solution for Dec10 {
+    type Input = Vec<Inst>;
+    type Output1 = isize;
+    type Output2 = String;
+
+    const PARAMS: &'static [Param] =
+        &[Param::ints("captures", &CAPTURES, "cycles whose signal strength part 1 adds up")];
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        parse_items(input.lines()?).collect()
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        Self::part_1_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn part_1_with(input: &Self::Input, params: &Params) -> Self::Output1 {
+        let cpu = Cpu::run(input.iter().copied(), params.ints("captures"));
+        cpu.signal_strength()
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        let cpu = Cpu::from_iter(input.iter().copied());
+        cpu.crt().to_owned()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs;
+    use crate::prop::{check, expect_eq, ints, vecs};
+    use crate::snapshot::assert_snapshot;
 
     #[test]
-    fn example_1() {
-        let input = "src/dec10/example_1.txt";
-        assert_eq!(13140, solve_1(input));
+    fn crt() {
+        for file in inputs::list("dec10").unwrap() {
+            let input = Dec10::parse(file.input()).unwrap();
+            assert_snapshot("dec10", &format!("crt/{}", file.name), &Dec10::part_2(&input));
+        }
     }
 
-    #[test]
-    fn puzzle_1() {
-        let input = "src/dec10/input_1.txt";
-        assert_eq!(15120, solve_1(input));
+    // the value of X during each cycle, in order
+    fn naive_xs(program: &[(bool, isize)]) -> Vec<isize> {
+        let mut x = 1;
+        let mut xs = Vec::new();
+        for &(addx, v) in program {
+            xs.push(x);
+            if addx {
+                xs.push(x);
+                x += v;
+            }
+        }
+        xs
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec10/example_1.txt";
-        let ex = "##..##..##..##..##..##..##..##..##..##..\n\
-                        ###...###...###...###...###...###...###.\n\
-                        ####....####....####....####....####....\n\
-                        #####.....#####.....#####.....#####.....\n\
-                        ######......######......######......####\n\
-                        #######.......#######.......#######.....";
-
-        assert_eq!(ex, &solve_2(input));
-    }
+    fn matches_reference() {
+        let program = vecs((ints(0u8..=1), ints(-5isize..=5)), 0..=150);
+        check("dec10", program, |program| {
+            let program: Vec<(bool, isize)> = program.iter().map(|&(k, v)| (k == 1, v)).collect();
+            let text: Vec<String> = program
+                .iter()
+                .map(|&(addx, v)| if addx { format!("addx {v}") } else { "noop".to_owned() })
+                .collect();
+            let input = Dec10::parse(Input::Str(&text.join("\n"))).map_err(|e| e.to_string())?;
+
+            let xs = naive_xs(&program);
+            let strength = [20, 60, 100, 140, 180, 220]
+                .into_iter()
+                .filter(|&c| c <= xs.len())
+                .map(|c| c as isize * xs[c - 1])
+                .sum();
+            let mut crt = String::new();
+            for (cycle, x) in xs.iter().enumerate() {
+                let pos = (cycle % 40) as isize;
+                if pos == 0 && cycle > 0 {
+                    crt.push('\n');
+                }
+                crt.push(if (x - pos).abs() <= 1 { '#' } else { '.' });
+            }
 
-    #[test]
-    fn puzzle_2() {
-        let input = "src/dec10/input_1.txt";
-        let ex = "###..#..#.###....##.###..###..#.....##..\n\
-                        #..#.#.#..#..#....#.#..#.#..#.#....#..#.\n\
-                        #..#.##...#..#....#.###..#..#.#....#..#.\n\
-                        ###..#.#..###.....#.#..#.###..#....####.\n\
-                        #.#..#.#..#....#..#.#..#.#....#....#..#.\n\
-                        #..#.#..#.#.....##..###..#....####.#..#.";
-        assert_eq!(ex, &solve_2(input));
+            expect_eq("part 1", strength, Dec10::part_1(&input))?;
+            expect_eq("part 2", crt, Dec10::part_2(&input))
+        });
     }
 }
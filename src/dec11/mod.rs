@@ -1,5 +1,11 @@
+use crate::checkpoint::{Checkpoint, Record, Records, Writer};
+use crate::params::{Param, Params};
+use crate::trace::event;
 use crate::utils::*;
+use crate::{Error, Part, Result, Solution};
 use std::collections::VecDeque;
+use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug)]
 enum Op {
@@ -10,13 +16,14 @@ enum Op {
 }
 
 impl Op {
-    fn apply(self, old: usize) -> usize {
+    // `None` if the new worry level overflows
+    fn apply(self, old: usize) -> Option<usize> {
         use Op::*;
         match self {
-            AddOld => (old) + (old),
-            Add(n) => (old) + (n),
-            MulOld => (old) * (old),
-            Mul(n) => (old) * (n),
+            AddOld => old.checked_add(old),
+            Add(n) => old.checked_add(n),
+            MulOld => old.checked_mul(old),
+            Mul(n) => old.checked_mul(n),
         }
     }
 
@@ -29,24 +36,63 @@ impl Op {
             Mul(n) => (old % modulo) * (n % modulo),
         }) % modulo
     }
+
+    fn from_token(record: &Record, idx: usize) -> Result<Self> {
+        use Op::*;
+        let token = record.text(idx)?;
+        let (add, operand) = match (token.strip_prefix('+'), token.strip_prefix('*')) {
+            (Some(operand), _) => (true, operand),
+            (_, Some(operand)) => (false, operand),
+            _ => {
+                let reason = format!("expected an operation like +old or *19, found {token:?}");
+                return Err(record.error(idx, reason));
+            }
+        };
+        Ok(match (add, operand) {
+            (true, "old") => AddOld,
+            (true, n) => Add(record.parse(n)?),
+            (false, "old") => MulOld,
+            (false, n) => Mul(record.parse(n)?),
+        })
+    }
+
+    // compact form for checkpoints, e.g. `*19` or `+old`
+    fn token(self) -> String {
+        use Op::*;
+        match self {
+            AddOld => "+old".to_owned(),
+            Add(n) => format!("+{n}"),
+            MulOld => "*old".to_owned(),
+            Mul(n) => format!("*{n}"),
+        }
+    }
 }
 
-impl From<String> for Op {
-    fn from(value: String) -> Self {
-        let [o, v] = value.split_whitespace().skip(4).next_chunk().unwrap();
+impl FromStr for Op {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let [o, v] = value
+            .split_whitespace()
+            .skip(4)
+            .next_array()
+            .ok_or_else(|| Error::parse(1, "expected \"Operation: new = old OP VALUE\""))?;
 
         use Op::*;
         match (o, v) {
-            ("+", "old") => AddOld,
-            ("+", v) => Add(must_parse(v)),
-            ("*", "old") => MulOld,
-            ("*", v) => Mul(must_parse(v)),
-            _ => unreachable!(),
+            ("+", "old") => Ok(AddOld),
+            ("+", v) => Ok(Add(parse_field(value, v)?)),
+            ("*", "old") => Ok(MulOld),
+            ("*", v) => Ok(Mul(parse_field(value, v)?)),
+            _ => Err(Error::parse(
+                col_of(value, o),
+                format!("expected + or *, found {o:?}"),
+            )),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Monkey {
     items: VecDeque<usize>,
     op: Op,
@@ -56,130 +102,451 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn turn(&mut self, output: &mut VecDeque<(usize, usize)>, anxiety_div: usize) {
+    // fails with the worry level of an item that overflows, which only happens with relief
+    fn turn(
+        &mut self,
+        output: &mut VecDeque<(usize, usize)>,
+        anxiety_div: usize,
+    ) -> Result<(), usize> {
         for old_item in self.items.drain(..) {
             self.processed_ct += 1;
 
             let new_item = if anxiety_div != 1 {
-                self.op.apply(old_item) / anxiety_div
+                self.op.apply(old_item).ok_or(old_item)? / anxiety_div
             } else {
                 self.op.apply_mod(old_item, self.modulo)
             };
 
             let (div, tm, fm) = self.test;
-            if new_item % div == 0 {
-                output.push_back((tm, new_item));
-            } else {
-                output.push_back((fm, new_item));
-            }
+            let to = if new_item % div == 0 { tm } else { fm };
+            event!(
+                "inspect",
+                old = old_item,
+                op = format_args!("{:?}", self.op),
+                new = new_item,
+                to = to
+            );
+            output.push_back((to, new_item));
+        }
+        Ok(())
+    }
+}
+
+impl Monkey {
+    fn last_number(line: &Line) -> Result<usize> {
+        match line.split_whitespace().last() {
+            Some(n) => line.parse_field(n),
+            None => Err(line.error(1, "expected a number")),
         }
     }
 }
 
-impl FromIterator<String> for Monkey {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let mut i = iter.into_iter().skip(1);
+impl FromLines for Monkey {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
+        let lines: Vec<Line> = lines.collect();
+        let [_, items, op, div, tm, fm] = &lines[..] else {
+            let reason = format!("expected 6 lines per monkey, found {}", lines.len());
+            return Err(Error::parse(1, reason).at_line(lines.first().map_or(1, |l| l.no)));
+        };
 
-        let items: VecDeque<usize> = i
-            .next()
-            .unwrap()
+        let items: VecDeque<usize> = items
             .split_whitespace()
             .skip(2)
             .map(|s: &str| s.strip_suffix(',').unwrap_or(s))
-            .map(must_parse)
-            .collect();
+            .map(|s| items.parse_field(s))
+            .collect::<Result<_>>()?;
 
-        let op = Op::from(i.next().unwrap());
+        let op = op.parse::<Op>()?;
 
-        let div: usize = must_parse(i.next().unwrap().split_whitespace().last().unwrap());
-        let tm: usize = must_parse(i.next().unwrap().split_whitespace().last().unwrap());
-        let fm: usize = must_parse(i.next().unwrap().split_whitespace().last().unwrap());
-        let test = (div, tm, fm);
+        let test = (
+            Self::last_number(div)?,
+            Self::last_number(tm)?,
+            Self::last_number(fm)?,
+        );
+        if test.0 == 0 {
+            return Err(div.error(div.len(), "cannot test divisibility by zero"));
+        }
 
-        Self {
+        Ok(Self {
             items,
             op,
             test,
             processed_ct: 0,
-            modulo: div,
-        }
+            modulo: test.0,
+        })
     }
 }
 
-#[derive(Debug)]
-struct Barrel {
+#[derive(Debug, Clone)]
+pub struct Barrel {
     monkeys: Vec<Monkey>,
     output: VecDeque<(usize, usize)>,
+    // played so far
+    rounds: usize,
 }
 
 impl Barrel {
-    fn run(&mut self, runs: usize, anxiety_div: usize) {
-        (0..runs).for_each(|_| {
-            self.round(anxiety_div);
+    // `lines` are where each monkey was read from, for reporting one that doesn't fit the rest
+    fn new(mut monkeys: Vec<Monkey>, lines: &[usize]) -> Result<Self> {
+        // monkey business needs the two busiest monkeys
+        if monkeys.len() < 2 {
+            let line = lines.first().copied().unwrap_or(1);
+            let reason = format!("expected at least two monkeys, found {}", monkeys.len());
+            return Err(Error::parse(1, reason).at_line(line));
+        }
+
+        for (idx, m) in monkeys.iter().enumerate() {
+            if let Some(&t) = [m.test.1, m.test.2].iter().find(|&&t| t >= monkeys.len()) {
+                let reason = format!("monkey {idx} throws to missing monkey {t}");
+                return Err(Error::parse(1, reason).at_line(lines[idx]));
+            }
+        }
+
+        // worry levels are kept below the product of the divisors and squared, so it must fit in
+        // half a usize
+        let mut modulo: usize = 1;
+        for (idx, m) in monkeys.iter().enumerate() {
+            modulo = modulo
+                .checked_mul(m.test.0)
+                .filter(|&p| p <= u32::MAX as usize)
+                .ok_or_else(|| {
+                    Error::parse(1, "product of the divisors is too large").at_line(lines[idx])
+                })?;
+        }
+        for monkey in &mut monkeys {
+            monkey.modulo = modulo;
+        }
+
+        Ok(Self {
+            monkeys,
+            output: VecDeque::new(),
+            rounds: 0,
         })
     }
 
-    fn round(&mut self, anxiety_div: usize) {
+    pub fn run(&mut self, runs: usize, anxiety_div: usize) -> Result<()> {
+        (0..runs).try_for_each(|_| self.round(anxiety_div))
+    }
+
+    // Without relief worry levels are kept small, but with it they're exact and can outgrow a
+    // usize, which fails the round.
+    pub fn round(&mut self, anxiety_div: usize) -> Result<()> {
         for idx in 0..self.monkeys.len() {
-            self.monkeys[idx].turn(&mut self.output, anxiety_div);
+            event!("turn", monkey = idx);
+            let turn = self.monkeys[idx].turn(&mut self.output, anxiety_div);
+            if let Err(worry) = turn {
+                let round = self.rounds + 1;
+                return Err(Error::unsolvable(format!(
+                    "round {round}: monkey {idx} can't inspect an item of worry level {worry} \
+                     without overflowing"
+                )));
+            }
             for (midx, item) in self.output.drain(..) {
                 self.monkeys[midx].items.push_back(item);
             }
         }
+        self.rounds += 1;
+        Ok(())
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    // how many items each monkey has inspected so far
+    pub fn inspections(&self) -> Vec<usize> {
+        self.monkeys.iter().map(|m| m.processed_ct).collect()
+    }
+
+    // product of the two highest inspection counts so far
+    pub fn monkey_business(&self) -> Result<usize> {
+        let mut out = self.inspections();
+        out.sort();
+        out.reverse();
+        out[0]
+            .checked_mul(out[1])
+            .ok_or_else(|| Error::unsolvable("monkey business overflows"))
     }
 }
 
-impl<I: Iterator<Item = String>> FromIterator<I> for Barrel {
-    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
-        let mut monkeys: Vec<Monkey> = iter.into_iter().map(Monkey::from_iter).collect();
+impl FromLines for Barrel {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
+        let mut monkeys = Vec::new();
+        let mut headers = Vec::new();
+        for group in groups(lines) {
+            let group: Vec<Line> = group.collect();
+            headers.push(group[0].no);
+            monkeys.push(Monkey::from_lines(group.into_iter())?);
+        }
+        Self::new(monkeys, &headers)
+    }
+}
 
-        let modulo: usize = monkeys.iter().map(|m| m.test.0).product();
-        for monkey in &mut monkeys {
-            monkey.modulo = modulo;
+// Between rounds every item is held by a monkey, so the state is the rounds played and each
+// monkey's inspection count, rules and items:
+//
+//   rounds <n>
+//   monkey <inspected> <op> <divisor> <if true> <if false> <items>...
+impl Checkpoint for Barrel {
+    const KIND: &'static str = "dec11";
+
+    fn save(&self, out: &mut Writer) {
+        out.record("rounds", &[&self.rounds]);
+        for m in &self.monkeys {
+            let (div, tm, fm) = m.test;
+            let rules: [&dyn Display; 5] = [&m.processed_ct, &m.op.token(), &div, &tm, &fm];
+            out.list("monkey", &rules, &m.items);
         }
+    }
 
-        Self {
-            monkeys,
-            output: VecDeque::new(),
+    fn load(records: &mut Records) -> Result<Self> {
+        let rounds = records.next("rounds")?.field(0)?;
+
+        let mut monkeys = Vec::new();
+        let mut lines = Vec::new();
+        for r in records.all("monkey") {
+            let test = (r.field(2)?, r.field(3)?, r.field(4)?);
+            if test.0 == 0 {
+                return Err(r.error(2, "cannot test divisibility by zero"));
+            }
+            monkeys.push(Monkey {
+                items: r.rest(5)?.into(),
+                op: Op::from_token(&r, 1)?,
+                test,
+                processed_ct: r.field(0)?,
+                modulo: test.0,
+            });
+            lines.push(r.line());
         }
+        if monkeys.is_empty() {
+            // reports the record that should have been a monkey
+            records.next("monkey")?;
+        }
+
+        let mut barrel = Self::new(monkeys, &lines)?;
+        barrel.rounds = rounds;
+        Ok(barrel)
     }
 }
 
-fn solve(input: &str, runs: usize, anxiety_div: usize) -> usize {
-    let mut barrel = Barrel::from_iter(file_groups(input));
-    barrel.run(runs, anxiety_div);
+pub struct Dec11;
+
+impl Solution for Dec11 {
+    type Input = Barrel;
+    type Output1 = Result<usize>;
+    type Output2 = Result<usize>;
+
+    // with relief, worry levels are divided rather than reduced modulo the divisors, so playing
+    // enough rounds fails once one overflows
+    const PARAMS: &'static [Param] = &[
+        Param::int("rounds_1", 20, "rounds played in part 1"),
+        Param::int("relief_1", 3, "part 1 worry divisor, 1 for none").at_least(1),
+        Param::int("rounds_2", 10_000, "rounds played in part 2"),
+        Param::int("relief_2", 1, "part 2 worry divisor, 1 for none").at_least(1),
+    ];
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        Barrel::from_lines(input.lines()?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        Self::part_1_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        Self::part_2_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn part_1_with(input: &Self::Input, params: &Params) -> Self::Output1 {
+        monkey_business(input, params.int("rounds_1"), params.int("relief_1"))
+    }
+
+    fn part_2_with(input: &Self::Input, params: &Params) -> Self::Output2 {
+        monkey_business(input, params.int("rounds_2"), params.int("relief_2"))
+    }
+
+    // every monkey's inspection count, marking the two busiest
+    fn explain(input: &Self::Input, part: Part, params: &Params) -> Option<String> {
+        let (rounds, relief) = match part {
+            Part::One => (params.int("rounds_1"), params.int("relief_1")),
+            Part::Two => (params.int("rounds_2"), params.int("relief_2")),
+        };
+        let mut barrel = input.clone();
+        if let Err(e) = barrel.run(rounds, relief) {
+            return Some(e.to_string());
+        }
+
+        let counts = barrel.inspections();
+        let mut ranked: Vec<usize> = (0..counts.len()).collect();
+        ranked.sort_by_key(|&idx| std::cmp::Reverse(counts[idx]));
+        let mut out = vec![format!("inspections after {rounds} rounds:")];
+        for (idx, count) in counts.iter().enumerate() {
+            let busiest = if ranked.iter().take(2).any(|&r| r == idx) { " <- busiest" } else { "" };
+            out.push(format!("  monkey {idx}: {count}{busiest}"));
+        }
+        Some(out.join("\n"))
+    }
+}
 
-    let mut out: Vec<usize> = barrel.monkeys.into_iter().map(|m| m.processed_ct).collect();
-    out.sort();
-    out.reverse();
-    out[0] * out[1]
+fn monkey_business(barrel: &Barrel, runs: usize, anxiety_div: usize) -> Result<usize> {
+    let mut barrel = barrel.clone();
+    barrel.run(runs, anxiety_div)?;
+    barrel.monkey_business()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checkpoint;
+    use crate::prop::{check, expect_eq, ints, pick, vecs};
 
     #[test]
-    fn example_1() {
-        let input = "src/dec11/example_1.txt";
-        assert_eq!(10605, solve(input, 20, 3));
+    fn overflow() {
+        let barrel = Dec11::parse(include_str!("examples/1.txt").into()).unwrap();
+        let rounds = [("rounds_1".to_owned(), "100".to_owned())];
+        let params = Params::with(Dec11::PARAMS, &rounds).unwrap();
+        let err = Dec11::part_1_with(&barrel, &params).err().unwrap();
+        assert!(err.to_string().ends_with("without overflowing"), "{err}");
+
+        // without relief worry levels stay small however long the monkeys play
+        let rounds = [
+            ("rounds_1".to_owned(), "100".to_owned()),
+            ("relief_1".to_owned(), "1".to_owned()),
+        ];
+        let params = Params::with(Dec11::PARAMS, &rounds).unwrap();
+        assert!(Dec11::part_1_with(&barrel, &params).is_ok());
     }
 
     #[test]
-    fn puzzle_1() {
-        let input = "src/dec11/input_1.txt";
-        assert_eq!(99852, solve(input, 20, 3));
+    fn resumes() {
+        let mut barrel = Dec11::parse(include_str!("examples/1.txt").into()).unwrap();
+        barrel.run(20, 1).unwrap();
+        let saved = checkpoint::save(&barrel);
+        assert_eq!(include_str!("checkpoints/example-round-20.txt"), saved);
+
+        let mut barrel: Barrel = checkpoint::load(saved.as_str().into()).unwrap();
+        assert_eq!(20, barrel.rounds());
+        assert_eq!(vec![99, 97, 8, 103], barrel.inspections());
+        barrel.run(10_000 - barrel.rounds(), 1).unwrap();
+        assert_eq!(2713310158, barrel.monkey_business().unwrap());
+
+        let broken = saved.replace("+6 19 2 0", "+6 19 2 4");
+        let err = checkpoint::load::<Barrel>(broken.as_str().into()).err().unwrap();
+        assert_eq!("<input>:4:1: monkey 1 throws to missing monkey 4", err.to_string());
+
+        let alone = "checkpoint dec11\nrounds 0\nmonkey 0 +1 2 0 0 5\n";
+        let err = checkpoint::load::<Barrel>(alone.into()).err().unwrap();
+        assert_eq!("<input>:3:1: expected at least two monkeys, found 1", err.to_string());
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec11/example_1.txt";
-        assert_eq!(2713310158, solve(input, 10_000, 1));
+    fn too_few_monkeys() {
+        let first = include_str!("examples/1.txt").split("\n\n").next().unwrap();
+        let err = Dec11::parse(first.into()).err().unwrap();
+        assert_eq!("line 1, column 1: expected at least two monkeys, found 1", err.to_string());
+        let err = Dec11::parse("".into()).err().unwrap();
+        assert_eq!("line 1, column 1: expected at least two monkeys, found 0", err.to_string());
+    }
+
+    // items, operation and operand, divisor, and throw targets as offsets from the monkey
+    type Spec = ((Vec<u64>, usize, u64), (u64, usize, usize));
+
+    const OPS: [&str; 4] = ["+ old", "+", "* old", "*"];
+
+    fn targets(idx: usize, n: usize, (t, f): (usize, usize)) -> (usize, usize) {
+        // a lone monkey has no one to throw to, which parsing rejects
+        if n < 2 {
+            return (idx, idx);
+        }
+        // offsets never wrap around to the monkey itself
+        let to = |off: usize| (idx + 1 + (off - 1) % (n - 1)) % n;
+        (to(t), to(f))
+    }
+
+    fn render(specs: &[Spec]) -> String {
+        let monkeys: Vec<String> = specs
+            .iter()
+            .enumerate()
+            .map(|(idx, ((items, op, operand), (div, t, f)))| {
+                let items: Vec<String> = items.iter().map(u64::to_string).collect();
+                let op = match *op {
+                    0 | 2 => OPS[*op].to_owned(),
+                    _ => format!("{} {operand}", OPS[*op]),
+                };
+                let (t, f) = targets(idx, specs.len(), (*t, *f));
+                format!(
+                    "Monkey {idx}:\n  Starting items: {}\n  Operation: new = old {op}\n  \
+                     Test: divisible by {div}\n    If true: throw to monkey {t}\n    \
+                     If false: throw to monkey {f}",
+                    items.join(", ")
+                )
+            })
+            .collect();
+        monkeys.join("\n\n")
+    }
+
+    // Plays every round with wide worry levels, kept modulo the product of the divisors when
+    // there's no relief and exact otherwise, giving up with `None` once an exact one outgrows a
+    // `usize`, which the solution reports as an error.
+    fn naive(specs: &[Spec], rounds: usize, anxiety_div: u128) -> Option<usize> {
+        let modulo: u128 = specs.iter().map(|(_, (div, ..))| *div as u128).product();
+        let mut items: Vec<VecDeque<u128>> = specs
+            .iter()
+            .map(|((items, ..), _)| items.iter().map(|&i| i as u128).collect())
+            .collect();
+        let mut counts = vec![0; specs.len()];
+
+        for _ in 0..rounds {
+            for (idx, ((_, op, operand), (div, t, f))) in specs.iter().enumerate() {
+                let (t, f) = targets(idx, specs.len(), (*t, *f));
+                while let Some(old) = items[idx].pop_front() {
+                    counts[idx] += 1;
+                    let new = match op {
+                        0 => old + old,
+                        1 => old + *operand as u128,
+                        2 => old * old,
+                        _ => old * *operand as u128,
+                    };
+                    let new = if anxiety_div == 1 {
+                        new % modulo
+                    } else if new > usize::MAX as u128 {
+                        return None;
+                    } else {
+                        new / anxiety_div
+                    };
+                    items[if new.is_multiple_of(*div as u128) { t } else { f }].push_back(new);
+                }
+            }
+        }
+
+        counts.sort();
+        Some(counts[counts.len() - 1] * counts[counts.len() - 2])
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec11/input_1.txt";
-        assert_eq!(25935263541, solve(input, 10_000, 1));
+    fn matches_reference() {
+        let monkey = (
+            (vecs(ints(1u64..=99), 0..=4), ints(0usize..=3), ints(1u64..=9)),
+            (pick(&[2, 3, 5, 7, 11, 13, 17, 19]), ints(1usize..=3), ints(1usize..=3)),
+        );
+        let runs = (ints(1usize..=8), pick(&[1usize, 3]));
+        check("dec11", (vecs(monkey, 1..=4), runs), |(specs, (rounds, div))| {
+            let parsed = Barrel::from_lines(Input::Str(&render(specs)).lines().unwrap());
+            if specs.len() < 2 {
+                let err = parsed.err().map(|e| e.to_string()).unwrap_or_default();
+                let expected = "line 1, column 1: expected at least two monkeys, found 1";
+                return expect_eq("error", expected, &err);
+            }
+
+            let mut barrel = parsed.map_err(|e| e.to_string())?;
+            let run = barrel.run(*rounds, *div);
+            match naive(specs, *rounds, *div as u128) {
+                Some(expected) => {
+                    run.map_err(|e| e.to_string())?;
+                    expect_eq("monkey business", expected, barrel.monkey_business().unwrap())
+                }
+                None => expect_eq("overflows", true, run.is_err()),
+            }
+        });
     }
 }
@@ -1,9 +1,10 @@
 use crate::utils::*;
-use std::collections::{BTreeSet, HashMap};
+use crate::{Error, Result, Solution, Variant};
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::ops::{Index, IndexMut};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
-struct Idx(usize, usize);
+pub struct Idx(pub usize, pub usize);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
 struct DistIdx(usize, Idx);
@@ -14,26 +15,38 @@ impl DistIdx {
     }
 }
 
-struct Square {
+pub struct Square {
     elevation: u32,
     neighbors: Vec<Idx>,
 }
 
-impl From<char> for Square {
-    fn from(value: char) -> Self {
+impl Square {
+    pub fn elevation(&self) -> u32 {
+        self.elevation
+    }
+}
+
+impl TryFrom<char> for Square {
+    type Error = Error;
+
+    fn try_from(value: char) -> Result<Self> {
         let elevation = match value {
             'S' => 'a' as u32,
             'E' => 'z' as u32,
-            _ => value as u32,
+            'a'..='z' => value as u32,
+            _ => {
+                let reason = format!("expected an elevation, found {value:?}");
+                return Err(Error::parse(1, reason));
+            }
         };
-        Self {
+        Ok(Self {
             elevation,
             neighbors: Vec::new(),
-        }
+        })
     }
 }
 
-struct Mountain {
+pub struct Mountain {
     squares: Vec<Vec<Square>>,
     start: Idx,
     end: Idx,
@@ -85,19 +98,62 @@ impl Mountain {
         (dist, prev)
     }
 
-    fn shortest_path(&self, start: Idx) -> usize {
+    pub fn start(&self) -> Idx {
+        self.start
+    }
+
+    pub fn end(&self) -> Idx {
+        self.end
+    }
+
+    // every step costs the same, so a breadth-first search finds the same distances as `walk`
+    pub fn bfs(&self) -> HashMap<Idx, usize> {
+        let mut dist: HashMap<Idx, usize> = HashMap::new();
+        let mut queue: VecDeque<Idx> = VecDeque::new();
+
+        dist.insert(self.end, 0);
+        queue.push_back(self.end);
+
+        while let Some(idx) = queue.pop_front() {
+            let alt = dist[&idx] + 1;
+            for v in &self[idx].neighbors {
+                if !dist.contains_key(v) {
+                    dist.insert(*v, alt);
+                    queue.push_back(*v);
+                }
+            }
+        }
+
+        dist
+    }
+
+    pub fn shortest_path(&self, start: Idx) -> Result<usize> {
         let (dist, _) = self.walk();
-        dist[&start]
+        Self::distance(&dist, start)
     }
 
-    fn shortest_start(&self) -> usize {
+    pub fn shortest_start(&self) -> Result<usize> {
         let (dist, _) = self.walk();
+        self.nearest_start(&dist)
+    }
 
+    // squares missing from `dist` can't climb to the end at all
+    fn distance(dist: &HashMap<Idx, usize>, start: Idx) -> Result<usize> {
+        dist.get(&start).copied().ok_or_else(|| {
+            let Idx(r, c) = start;
+            let reason = format!("the end can't be reached from row {}, column {}", r + 1, c + 1);
+            Error::unsolvable(reason)
+        })
+    }
+
+    fn nearest_start(&self, dist: &HashMap<Idx, usize>) -> Result<usize> {
         self.maybe_starts
             .iter()
             .filter_map(|idx| dist.get(idx).copied())
             .min()
-            .unwrap()
+            .ok_or_else(|| {
+                Error::unsolvable("the end can't be reached from any square at elevation a")
+            })
     }
 }
 
@@ -115,31 +171,47 @@ impl IndexMut<Idx> for Mountain {
     }
 }
 
-impl FromIterator<String> for Mountain {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let mut squares = Vec::new();
-        let mut start = Idx(0, 0);
-        let mut end = Idx(0, 0);
+impl FromLines for Mountain {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
+        let mut squares: Vec<Vec<Square>> = Vec::new();
+        let mut start = None;
+        let mut end = None;
         let mut maybe_starts = Vec::new();
 
-        for (r, line) in iter.into_iter().enumerate() {
+        for (r, line) in lines.enumerate() {
             let mut row = Vec::new();
             for (c, ch) in line.chars().enumerate() {
-                let sq = Square::from(ch);
+                let sq = Square::try_from(ch).map_err(|e| e.offset(c).at_line(line.no))?;
                 match ch {
+                    'S' if start.is_some() => return Err(line.error(c + 1, "duplicate start")),
+                    'E' if end.is_some() => return Err(line.error(c + 1, "duplicate end")),
                     'S' => {
-                        start = Idx(r, c);
+                        start = Some(Idx(r, c));
                         maybe_starts.push(Idx(r, c));
                     }
-                    'E' => end = Idx(r, c),
+                    'E' => end = Some(Idx(r, c)),
                     'a' => maybe_starts.push(Idx(r, c)),
                     _ => {}
                 };
                 row.push(sq);
             }
+
+            if row.is_empty() {
+                return Err(line.error(1, "expected a row of squares"));
+            }
+
+            let cols = squares.first().map_or(row.len(), Vec::len);
+            if row.len() != cols {
+                let reason = format!("expected {cols} squares, found {}", row.len());
+                return Err(line.error(1, reason));
+            }
             squares.push(row);
         }
 
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(Error::parse(1, "expected both a start and an end square"));
+        };
+
         let mut mountain = Self {
             squares,
             start,
@@ -157,45 +229,130 @@ impl FromIterator<String> for Mountain {
             }
         }
 
-        mountain
+        Ok(mountain)
     }
 }
 
-fn solve_1(input: &str) -> usize {
-    let mountain = Mountain::from_iter(file_lines(input));
-    mountain.shortest_path(mountain.start)
-}
+pub struct Dec12;
+
+impl Solution for Dec12 {
+    type Input = Mountain;
+    type Output1 = Result<usize>;
+    type Output2 = Result<usize>;
 
-fn solve_2(input: &str) -> usize {
-    let mountain = Mountain::from_iter(file_lines(input));
-    mountain.shortest_start()
+    const VARIANTS_1: &'static [Variant<Mountain, Result<usize>>] =
+        &[("bfs", |m, _| Mountain::distance(&m.bfs(), m.start))];
+    const VARIANTS_2: &'static [Variant<Mountain, Result<usize>>] =
+        &[("bfs", |m, _| m.nearest_start(&m.bfs()))];
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        Mountain::from_lines(input.lines()?)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input.shortest_path(input.start)
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        input.shortest_start()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, ints, pick, vecs};
+
+    // climbs from `from` towards the end with a fresh search, ignoring the distances from the end
+    fn naive_dist(grid: &[Vec<char>], from: Idx) -> Option<usize> {
+        let elevation = |Idx(r, c): Idx| match grid[r][c] {
+            'S' => 'a',
+            'E' => 'z',
+            ch => ch,
+        } as u32;
+
+        let mut seen = vec![from];
+        let mut frontier = vec![from];
+        for steps in 0.. {
+            if frontier.is_empty() {
+                return None;
+            }
+            if frontier.iter().any(|&Idx(r, c)| grid[r][c] == 'E') {
+                return Some(steps);
+            }
 
-    #[test]
-    fn example_1() {
-        let input = "src/dec12/example_1.txt";
-        assert_eq!(31, solve_1(input));
+            let mut next = Vec::new();
+            for &idx in &frontier {
+                for (dr, dc) in DIR_DELTAS {
+                    let (r, c) = (idx.0 as isize + dr, idx.1 as isize + dc);
+                    if grid.get(r as usize).and_then(|row| row.get(c as usize)).is_none() {
+                        continue;
+                    }
+                    let to = Idx(r as usize, c as usize);
+                    if elevation(to) <= elevation(idx) + 1 && !seen.contains(&to) {
+                        seen.push(to);
+                        next.push(to);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        unreachable!()
     }
 
     #[test]
-    fn puzzle_1() {
-        let input = "src/dec12/input_1.txt";
-        assert_eq!(447, solve_1(input));
+    fn unreachable() {
+        // the start is walled in, but the a beyond the wall climbs to the end
+        let walled = "Szabcdefghijklmnopqrstuvwxyz\n\
+                      zzzzzzzzzzzzzzzzzzzzzzzzzzzE";
+        let mountain = Dec12::parse(walled.into()).unwrap();
+        assert_eq!(26, Dec12::part_2(&mountain).unwrap());
+        let err = Dec12::part_1(&mountain).err().unwrap();
+        assert_eq!("no answer: the end can't be reached from row 1, column 1", err.to_string());
+
+        let mountain = Dec12::parse("ScE".into()).unwrap();
+        let err = Dec12::part_2(&mountain).err().unwrap();
+        assert_eq!(
+            "no answer: the end can't be reached from any square at elevation a",
+            err.to_string()
+        );
     }
 
     #[test]
-    fn example_2() {
-        let input = "src/dec12/example_1.txt";
-        assert_eq!(29, solve_2(input));
-    }
+    fn matches_reference() {
+        let cells = 25;
+        let grid = (
+            (ints(1usize..=5), ints(2usize..=5)),
+            vecs(pick(&['a', 'b', 'w', 'x', 'y', 'z']), cells..=cells),
+            (ints(0usize..=cells - 1), ints(0usize..=cells - 1)),
+        );
+        check("dec12", grid, |((rows, cols), squares, (s, e))| {
+            let n = rows * cols;
+            let (s, e) = (s % n, e % n);
+            let e = if e == s { (s + 1) % n } else { e };
+            let mut squares = squares[..n].to_vec();
+            (squares[s], squares[e]) = ('S', 'E');
+            let grid: Vec<Vec<char>> = squares.chunks(*cols).map(<[char]>::to_vec).collect();
+
+            let text: Vec<String> = grid.iter().map(|row| row.iter().collect()).collect();
+            let mountain = Dec12::parse(Input::Str(&text.join("\n"))).map_err(|e| e.to_string())?;
+
+            let (walked, _) = mountain.walk();
+            let searched = mountain.bfs();
+            let mut nearest: Option<usize> = None;
+            for idx in mountain.all_vertices() {
+                let expected = naive_dist(&grid, idx);
+                expect_eq(&format!("{idx:?} walk"), expected, walked.get(&idx).copied())?;
+                expect_eq(&format!("{idx:?} bfs"), expected, searched.get(&idx).copied())?;
+                if let (Some(d), 'a' | 'S') = (expected, grid[idx.0][idx.1]) {
+                    nearest = Some(nearest.map_or(d, |n| n.min(d)));
+                }
+            }
 
-    #[test]
-    fn puzzle_2() {
-        let input = "src/dec12/input_1.txt";
-        assert_eq!(446, solve_2(input));
+            // an end that can't be reached is an error rather than an answer
+            let expected = naive_dist(&grid, mountain.start());
+            expect_eq("part 1", expected, Dec12::part_1(&mountain).ok())?;
+            expect_eq("part 2", nearest, Dec12::part_2(&mountain).ok())
+        });
     }
 }
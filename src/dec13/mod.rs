@@ -1,138 +1,415 @@
+use crate::params::Params;
 use crate::utils::*;
+use crate::{Error, Part, Result, Solution};
 use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
 use std::iter::Peekable;
-use std::str::Chars;
+use std::str::{CharIndices, FromStr};
+
+// parsing, comparing and dropping packets all recurse, so deeper lists would overflow the stack
+const MAX_NESTING: usize = 256;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum PacketDatum {
+pub enum PacketDatum {
     Int(usize),
     List(Vec<PacketDatum>),
 }
 
 impl PacketDatum {
-    fn parse_list(chars: &mut Peekable<Chars>) -> Self {
-        chars.next(); // [
+    // `len` is the length of the whole packet, for reporting a missing `]` at the end of the line,
+    // and `depth` the number of lists this one is inside
+    fn parse_list(chars: &mut Peekable<CharIndices>, len: usize, depth: usize) -> Result<Self> {
+        match chars.next() {
+            Some((idx, '[')) if depth >= MAX_NESTING => {
+                let reason = format!("lists nested deeper than {MAX_NESTING} are not supported");
+                return Err(Error::parse(idx + 1, reason));
+            }
+            Some((_, '[')) => {}
+            Some((idx, ch)) => {
+                return Err(Error::parse(idx + 1, format!("expected '[', found {ch:?}")))
+            }
+            None => return Err(Error::parse(len + 1, "expected '['")),
+        }
 
         let mut list = Vec::new();
-        while let Some(&ch) = chars.peek() {
+        while let Some(&(idx, ch)) = chars.peek() {
             match ch {
                 ',' => {
                     chars.next();
                 }
                 ']' => {
                     chars.next(); // ]
-                    return PacketDatum::List(list);
+                    return Ok(PacketDatum::List(list));
                 }
-                '[' => list.push(Self::parse_list(chars)),
-                _ => list.push(Self::parse_int(chars)),
+                '[' => list.push(Self::parse_list(chars, len, depth + 1)?),
+                '0'..='9' => list.push(Self::parse_int(chars)?),
+                _ => return Err(Error::parse(idx + 1, format!("unexpected {ch:?}"))),
             }
         }
 
-        unreachable!()
+        Err(Error::parse(len + 1, "expected ']'"))
     }
 
-    fn parse_int(chars: &mut Peekable<Chars>) -> Self {
-        let mut digits = Vec::new();
-        while chars.peek().unwrap().is_ascii_digit() {
-            digits.push(chars.next().unwrap());
+    fn parse_int(chars: &mut Peekable<CharIndices>) -> Result<Self> {
+        let start = chars.peek().map_or(0, |&(idx, _)| idx);
+        let mut digits = String::new();
+        while let Some((_, ch)) = chars.next_if(|(_, ch)| ch.is_ascii_digit()) {
+            digits.push(ch);
         }
 
-        let s: String = digits.iter().collect();
-        PacketDatum::Int(must_parse(&s))
+        digits
+            .parse()
+            .map(PacketDatum::Int)
+            .map_err(|e| Error::from(e).offset(start))
     }
 }
 
-impl From<String> for PacketDatum {
-    fn from(value: String) -> Self {
-        let mut chars = value.chars().peekable();
-        PacketDatum::parse_list(&mut chars)
+impl FromStr for PacketDatum {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut chars = value.char_indices().peekable();
+        let packet = PacketDatum::parse_list(&mut chars, value.len(), 0)?;
+
+        match chars.next() {
+            None => Ok(packet),
+            Some((idx, ch)) => Err(Error::parse(
+                idx + 1,
+                format!("unexpected {ch:?} after packet"),
+            )),
+        }
     }
 }
 
 impl PartialOrd for PacketDatum {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PacketDatum {
+    fn cmp(&self, other: &Self) -> Ordering {
         use PacketDatum::*;
 
         match (self, other) {
-            (Int(l), Int(r)) => l.partial_cmp(r),
-            (List(l), List(r)) => l.partial_cmp(r),
-            (Int(l), List(r)) => (vec![Int(*l)]).partial_cmp(r),
-            (List(l), Int(r)) => l.partial_cmp(&vec![Int(*r)]),
+            (Int(l), Int(r)) => l.cmp(r),
+            (List(l), List(r)) => l.cmp(r),
+            (Int(l), List(r)) => [Int(*l)][..].cmp(r),
+            (List(l), Int(r)) => l[..].cmp(&[Int(*r)]),
         }
     }
 }
 
-impl Ord for PacketDatum {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl Display for PacketDatum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PacketDatum::Int(n) => write!(f, "{n}"),
+            PacketDatum::List(items) => {
+                write!(f, "[")?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+        }
     }
 }
 
-struct Pair(PacketDatum, PacketDatum);
+pub struct Pair(pub PacketDatum, pub PacketDatum);
+
+impl Pair {
+    pub fn in_order(&self) -> bool {
+        self.0 <= self.1
+    }
+
+    // The comparison that decides the order, following `cmp`, with the indices leading to it.
+    // `None` if the packets are equal.
+    fn difference(&self) -> Option<String> {
+        let mut path = Vec::new();
+        let reason = Self::decide(&self.0, &self.1, &mut path)?;
+        let at: String = path.iter().map(|idx| format!("[{idx}]")).collect();
+        Some(if at.is_empty() { reason } else { format!("at {at}: {reason}") })
+    }
+
+    fn decide(left: &PacketDatum, right: &PacketDatum, path: &mut Vec<usize>) -> Option<String> {
+        use PacketDatum::*;
 
-impl FromIterator<String> for Pair {
-    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
-        let mut i = iter.into_iter();
-        Self(
-            PacketDatum::from(i.next().unwrap()),
-            PacketDatum::from(i.next().unwrap()),
-        )
+        match (left, right) {
+            (Int(l), Int(r)) => match l.cmp(r) {
+                Ordering::Less => Some(format!("{l} < {r}")),
+                Ordering::Greater => Some(format!("{l} > {r}")),
+                Ordering::Equal => None,
+            },
+            (List(l), List(r)) => {
+                for (idx, (a, b)) in l.iter().zip(r).enumerate() {
+                    path.push(idx);
+                    if let Some(reason) = Self::decide(a, b, path) {
+                        return Some(reason);
+                    }
+                    path.pop();
+                }
+                match l.len().cmp(&r.len()) {
+                    Ordering::Less => Some(format!("left runs out first, {left} vs {right}")),
+                    Ordering::Greater => Some(format!("right runs out first, {left} vs {right}")),
+                    Ordering::Equal => None,
+                }
+            }
+            (Int(l), List(_)) => Self::decide(&List(vec![Int(*l)]), right, path)
+                .map(|reason| format!("left {l} as [{l}], {reason}")),
+            (List(_), Int(r)) => Self::decide(left, &List(vec![Int(*r)]), path)
+                .map(|reason| format!("right {r} as [{r}], {reason}")),
+        }
     }
 }
 
-fn solve_1(input: &str) -> usize {
-    file_groups(input)
-        .map(Pair::from_iter)
-        .enumerate()
-        .filter(|(_, p)| p.0 <= p.1)
-        .map(|(i, _)| i + 1)
-        .sum()
+impl FromLines for Pair {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
+        let lines: Vec<Line> = lines.collect();
+        match &lines[..] {
+            [left, right] => Ok(Self(left.parse()?, right.parse()?)),
+            _ => {
+                let reason = format!("expected a pair of packets, found {} lines", lines.len());
+                Err(Error::parse(1, reason).at_line(lines.first().map_or(1, |l| l.no)))
+            }
+        }
+    }
 }
 
-fn solve_2(input: &str) -> usize {
-    use PacketDatum::*;
-    let start = List(vec![List(vec![Int(2)])]);
-    let end = List(vec![List(vec![Int(6)])]);
+pub struct Dec13;
+
+impl Solution for Dec13 {
+    type Input = Vec<Pair>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        input.groups()?.map(Pair::from_lines).collect()
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.in_order())
+            .map(|(i, _)| i + 1)
+            .sum()
+    }
+
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        use PacketDatum::*;
+        let start = List(vec![List(vec![Int(2)])]);
+        let end = List(vec![List(vec![Int(6)])]);
 
-    let mut packets: Vec<PacketDatum> = file_lines(input)
-        .filter(|s| !s.is_empty())
-        .map(PacketDatum::from)
-        .collect();
+        let mut packets: Vec<PacketDatum> = input
+            .iter()
+            .flat_map(|p| [p.0.clone(), p.1.clone()])
+            .collect();
 
-    packets.push(start.clone());
-    packets.push(end.clone());
-    packets.sort();
+        packets.push(start.clone());
+        packets.push(end.clone());
+        packets.sort();
+
+        let s = packets.binary_search(&start).unwrap() + 1;
+        let e = packets.binary_search(&end).unwrap() + 1;
+        s * e
+    }
 
-    let s = packets.binary_search(&start).unwrap() + 1;
-    let e = packets.binary_search(&end).unwrap() + 1;
-    s * e
+    // whether each pair is in order and why, or where the dividers sort to
+    fn explain(input: &Self::Input, part: Part, _params: &Params) -> Option<String> {
+        let mut out = Vec::new();
+        match part {
+            Part::One => {
+                let mut ordered = Vec::new();
+                for (idx, pair) in input.iter().enumerate() {
+                    let verdict = if pair.in_order() { "in order" } else { "out of order" };
+                    let why = pair.difference().unwrap_or("equal".to_owned());
+                    out.push(format!("pair {}: {verdict}, {why}", idx + 1));
+                    if pair.in_order() {
+                        ordered.push((idx + 1).to_string());
+                    }
+                }
+                out.push(format!("in order: {}", ordered.join(" + ")));
+            }
+            Part::Two => {
+                use PacketDatum::*;
+                let packets = || input.iter().flat_map(|p| [&p.0, &p.1]);
+                // each divider follows the packets below it, and [[6]] follows [[2]] as well
+                for (dividers_before, n) in [(0, 2), (1, 6)] {
+                    let divider = List(vec![List(vec![Int(n)])]);
+                    let below = packets().filter(|&p| *p < divider).count();
+                    let position = below + dividers_before + 1;
+                    out.push(format!("{divider} sorts to position {position}"));
+                }
+            }
+        }
+        Some(out.join("\n"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prop::{check, expect_eq, vecs, Gen};
+    use crate::rng::Rng;
+    use PacketDatum::*;
 
     #[test]
-    fn example_1() {
-        let input = "src/dec13/example_1.txt";
-        assert_eq!(13, solve_1(input));
+    fn parse_error() {
+        let err = "[1,[2,x]]".parse::<PacketDatum>().err().unwrap();
+        assert_eq!("column 7: unexpected 'x'", err.to_string());
+
+        let err = "[1,[2]".parse::<PacketDatum>().err().unwrap();
+        assert_eq!("column 7: expected ']'", err.to_string());
+
+        let deep = "[".repeat(200_000) + &"]".repeat(200_000);
+        let err = deep.parse::<PacketDatum>().err().unwrap();
+        assert_eq!(
+            "column 257: lists nested deeper than 256 are not supported",
+            err.to_string()
+        );
+        let limit = "[".repeat(MAX_NESTING) + &"]".repeat(MAX_NESTING);
+        assert!(limit.parse::<PacketDatum>().is_ok());
     }
 
     #[test]
-    fn puzzle_1() {
-        let input = "src/dec13/input_1.txt";
-        assert_eq!(5808, solve_1(input));
+    fn explains() {
+        let pairs = Dec13::parse(include_str!("examples/1.txt").into()).unwrap();
+        let params = Params::defaults(Dec13::PARAMS);
+        let working = Dec13::explain(&pairs, Part::One, &params).unwrap();
+        let lines: Vec<&str> = working.lines().collect();
+        assert_eq!("pair 2: in order, at [1][0]: right 4 as [4], 2 < 4", lines[1]);
+        assert_eq!(
+            "pair 7: out of order, at [0]: right runs out first, [[]] vs []",
+            lines[6]
+        );
+        assert_eq!("in order: 1 + 2 + 4 + 6", lines[8]);
+
+        let working = Dec13::explain(&pairs, Part::Two, &params).unwrap();
+        assert_eq!("[[2]] sorts to position 10\n[[6]] sorts to position 14", working);
     }
 
-    #[test]
-    fn example_2() {
-        let input = "src/dec13/example_1.txt";
-        assert_eq!(140, solve_2(input));
+    // lists nested up to three deep
+    struct Packets;
+
+    impl Packets {
+        fn datum(rng: &mut Rng, depth: u32) -> PacketDatum {
+            if depth == 0 || rng.below(3) > 0 {
+                Int(rng.below(11) as usize)
+            } else {
+                Self::list(rng, depth - 1)
+            }
+        }
+
+        fn list(rng: &mut Rng, depth: u32) -> PacketDatum {
+            let len = rng.below(5);
+            List((0..len).map(|_| Self::datum(rng, depth)).collect())
+        }
+    }
+
+    impl Gen for Packets {
+        type Value = PacketDatum;
+
+        fn generate(&self, rng: &mut Rng) -> PacketDatum {
+            Self::list(rng, 3)
+        }
+
+        // a list shrinks to any list inside it, to itself without one item, or by shrinking one
+        fn shrink(&self, value: &PacketDatum) -> Vec<PacketDatum> {
+            match value {
+                Int(0) => vec![],
+                Int(n) => vec![Int(0), Int(n - 1)],
+                List(items) => {
+                    let mut out: Vec<PacketDatum> =
+                        items.iter().filter(|i| matches!(i, List(_))).cloned().collect();
+                    for idx in 0..items.len() {
+                        let mut fewer = items.clone();
+                        fewer.remove(idx);
+                        out.push(List(fewer));
+                    }
+                    for (idx, item) in items.iter().enumerate() {
+                        for simpler in self.shrink(item) {
+                            let mut next = items.clone();
+                            next[idx] = simpler;
+                            out.push(List(next));
+                        }
+                    }
+                    out
+                }
+            }
+        }
+    }
+
+    fn render(packet: &PacketDatum) -> String {
+        match packet {
+            Int(n) => n.to_string(),
+            List(items) => {
+                let items: Vec<String> = items.iter().map(render).collect();
+                format!("[{}]", items.join(","))
+            }
+        }
+    }
+
+    // the comparison as the puzzle describes it
+    fn naive_cmp(left: &PacketDatum, right: &PacketDatum) -> Ordering {
+        match (left, right) {
+            (Int(l), Int(r)) => l.cmp(r),
+            (Int(l), List(_)) => naive_cmp(&List(vec![Int(*l)]), right),
+            (List(_), Int(r)) => naive_cmp(left, &List(vec![Int(*r)])),
+            (List(l), List(r)) => {
+                for idx in 0.. {
+                    match (l.get(idx), r.get(idx)) {
+                        (None, None) => return Ordering::Equal,
+                        (None, Some(_)) => return Ordering::Less,
+                        (Some(_), None) => return Ordering::Greater,
+                        (Some(a), Some(b)) => match naive_cmp(a, b) {
+                            Ordering::Equal => continue,
+                            ord => return ord,
+                        },
+                    }
+                }
+                unreachable!()
+            }
+        }
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec13/input_1.txt";
-        assert_eq!(22713, solve_2(input));
+    fn matches_reference() {
+        check("dec13", vecs((Packets, Packets), 0..=8), |pairs| {
+            let text: Vec<String> = pairs
+                .iter()
+                .map(|(l, r)| format!("{}\n{}", render(l), render(r)))
+                .collect();
+            let input = Dec13::parse(Input::Str(&text.join("\n\n"))).map_err(|e| e.to_string())?;
+
+            for (idx, (l, r)) in pairs.iter().enumerate() {
+                expect_eq(&format!("{idx}"), (l, r), (&input[idx].0, &input[idx].1))?;
+                expect_eq(&format!("{idx} cmp"), naive_cmp(l, r), l.cmp(r))?;
+            }
+
+            let in_order = pairs
+                .iter()
+                .enumerate()
+                .filter(|(_, (l, r))| naive_cmp(l, r) != Ordering::Greater)
+                .map(|(idx, _)| idx + 1)
+                .sum();
+            expect_eq("part 1", in_order, Dec13::part_1(&input))?;
+
+            // a divider's position is one more than the number of packets before it, which is
+            // ambiguous when a packet ties with it
+            let dividers = ["[[2]]", "[[6]]"].map(|d| d.parse::<PacketDatum>().unwrap());
+            let packets: Vec<&PacketDatum> = pairs.iter().flat_map(|(l, r)| [l, r]).collect();
+            let mut key = 1;
+            for (idx, d) in dividers.iter().enumerate() {
+                let ords: Vec<Ordering> = packets.iter().map(|p| naive_cmp(p, d)).collect();
+                if ords.contains(&Ordering::Equal) {
+                    return Ok(());
+                }
+                key *= 1 + idx + ords.iter().filter(|&&o| o == Ordering::Less).count();
+            }
+            expect_eq("part 2", key, Dec13::part_2(&input))
+        });
     }
 }
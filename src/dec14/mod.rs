@@ -1,24 +1,66 @@
+use crate::checkpoint::{Checkpoint, Records, Writer};
+use crate::trace::event;
 use crate::utils::*;
+use crate::{Error, Result, Solution};
 use std::fmt::{Debug, Formatter};
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 
 #[derive(Copy, Clone, Eq, PartialEq)]
-enum Matter {
+pub enum Matter {
     Air,
     Rock,
     Sand,
 }
 
-struct Wall {
+// the wall is as wide as it is deep, so deeper rock would take a grid too large to allocate
+pub(crate) const MAX_DEPTH: u32 = 1_000;
+
+pub struct Polyline(Vec<(isize, isize)>);
+
+impl FromStr for Polyline {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut points: Vec<(isize, isize)> = Vec::new();
+
+        for pt in value.split(" -> ") {
+            let col = col_of(value, pt);
+            let (x, y) = pt
+                .split_once(',')
+                .ok_or_else(|| Error::parse(col, format!("expected a point, found {pt:?}")))?;
+            let pos = (
+                parse_field::<u32>(value, x)? as isize,
+                parse_field::<u32>(value, y)? as isize,
+            );
+            if pos.1 > MAX_DEPTH as isize {
+                let reason = format!("rock deeper than {MAX_DEPTH} is not supported");
+                return Err(Error::parse(col_of(value, y), reason));
+            }
+
+            if let Some(prev) = points.last() {
+                if prev.0 != pos.0 && prev.1 != pos.1 {
+                    return Err(Error::parse(col, "lines must be horizontal or vertical"));
+                }
+            }
+            points.push(pos);
+        }
+
+        Ok(Self(points))
+    }
+}
+
+#[derive(Clone)]
+pub struct Wall {
     offset: isize,
     grid: Vec<Vec<Matter>>,
 }
 
 impl Wall {
-    fn new(input: &str) -> Self {
+    pub fn new(paths: &[Polyline]) -> Self {
         use Matter::*;
 
-        let max_y = Self::scan_height(input);
+        let max_y = Self::scan_height(paths);
         let height = 1 + max_y + 2;
         let (offset, width) = (500 - height, 2 * height);
 
@@ -27,60 +69,79 @@ impl Wall {
 
         let mut w = Self { offset, grid };
 
-        for line in file_lines(input) {
-            let mut coords = line
-                .split(" -> ")
-                .map(|s| s.split_once(',').unwrap())
-                .map(|(x, y)| (must_parse::<isize>(x), must_parse::<isize>(y)));
-            let mut start = coords.next().unwrap();
-            for end in coords {
-                let d = (
-                    (end.0 - start.0).clamp(-1, 1),
-                    (end.1 - start.1).clamp(-1, 1),
-                );
-                while start != end {
-                    w[start] = Rock;
-                    start.0 += d.0;
-                    start.1 += d.1;
-                }
+        for path in paths {
+            for (idx, &end) in path.0.iter().enumerate() {
+                w.add_line(path.0[idx.saturating_sub(1)], end);
             }
-            w[start] = Rock;
         }
 
         w
     }
 
-    fn scan_height(input: &str) -> isize {
-        file_lines(input)
-            .flat_map(|l| {
-                let v: Vec<isize> = l
-                    .split(" -> ")
-                    .map(|s| s.split_once(',').unwrap().1)
-                    .map(must_parse::<isize>)
-                    .collect();
-                v
-            })
+    fn scan_height(paths: &[Polyline]) -> isize {
+        paths
+            .iter()
+            .flat_map(|p| p.0.iter().map(|&(_, y)| y))
             .max()
-            .unwrap()
+            .unwrap_or(0)
     }
 
-    fn drop_to_bottom(&mut self) -> usize {
-        let mut ct = 0;
-        let target = (self.grid.len() - 2) as isize;
-        let mut prev = vec![(500, 0)];
-        while self.drop_sand(&mut prev).1 < target {
-            ct += 1;
+    // Lines are horizontal or vertical, so this fills exactly the points between the ends. Rock
+    // beyond the reach of the sand pile would fall outside the grid, so is dropped.
+    fn add_line(&mut self, from: (isize, isize), to: (isize, isize)) {
+        let last_col = self.offset + self.grid[0].len() as isize - 1;
+        let xs = from.0.min(to.0).max(self.offset)..=from.0.max(to.0).min(last_col);
+        for x in xs {
+            for y in from.1.min(to.1)..=from.1.max(to.1) {
+                self[(x, y)] = Matter::Rock;
+            }
         }
-        ct
     }
 
-    fn drop_to_top(&mut self) -> usize {
-        let mut ct = 0;
+    // sand resting before any falls to the floor, counting what was already there
+    pub fn drop_to_bottom(&mut self) -> usize {
+        self.pour(usize::MAX, false);
+        self.resting(false)
+    }
+
+    // sand resting once the source is buried, counting what was already there
+    pub fn drop_to_top(&mut self) -> usize {
+        self.pour(usize::MAX, true);
+        self.resting(true)
+    }
+
+    // Drops up to `limit` units of sand, stopping early once the source is buried or, unless
+    // `floor`, a unit falls to the floor. Returns whether it stopped early.
+    pub fn pour(&mut self, limit: usize, floor: bool) -> bool {
+        let bottom = self.bottom();
+        let done = |w: &Self| {
+            w[(500, 0)] == Matter::Sand || (!floor && w.grid[bottom].contains(&Matter::Sand))
+        };
+        if done(self) {
+            return true;
+        }
+
+        // the path the last unit fell by, which the next follows until it's blocked
         let mut prev = vec![(500, 0)];
-        while self.drop_sand(&mut prev) != (500, 0) {
-            ct += 1;
+        for _ in 0..limit {
+            let pos = self.drop_sand(&mut prev);
+            if pos == (500, 0) || (!floor && pos.1 == bottom as isize) {
+                return true;
+            }
         }
-        ct + 1
+        false
+    }
+
+    // units of sand at rest, leaving out any on the floor unless `floor`
+    fn resting(&self, floor: bool) -> usize {
+        let rows = if floor { self.bottom() + 1 } else { self.bottom() };
+        let sand = self.grid[..rows].iter().flatten();
+        sand.filter(|&&m| m == Matter::Sand).count()
+    }
+
+    // the row just above the floor
+    fn bottom(&self) -> usize {
+        self.grid.len() - 2
     }
 
     fn drop_sand(&mut self, prev: &mut Vec<(isize, isize)>) -> (isize, isize) {
@@ -105,6 +166,7 @@ impl Wall {
 
             prev.pop();
             self[pos] = Matter::Sand;
+            event!("rest", x = pos.0, y = pos.1);
             return pos;
         }
 
@@ -120,7 +182,8 @@ impl Debug for Wall {
                 .iter()
                 .enumerate()
                 .map(|(c, tile)| match &tile {
-                    _ if (c + self.offset as usize, r) == (500, 0) => '+',
+                    // the offset is negative once the wall is deeper than the source is far from 0
+                    _ if (c as isize + self.offset, r) == (500, 0) => '+',
                     Air => ' ',
                     Rock => '#',
                     Sand => 'o',
@@ -132,6 +195,63 @@ impl Debug for Wall {
     }
 }
 
+// Every row above the floor, as `.` for air, `#` for rock and `o` for sand. The wall is always
+// twice as wide as it is tall and centred on the source, so the rows are all there is to it.
+//
+//   row <cells>
+impl Checkpoint for Wall {
+    const KIND: &'static str = "dec14";
+
+    fn save(&self, out: &mut Writer) {
+        for row in &self.grid[..self.grid.len() - 1] {
+            let cells: String = row
+                .iter()
+                .map(|m| match m {
+                    Matter::Air => '.',
+                    Matter::Rock => '#',
+                    Matter::Sand => 'o',
+                })
+                .collect();
+            out.record("row", &[&cells]);
+        }
+    }
+
+    fn load(records: &mut Records) -> Result<Self> {
+        let rows = records.all("row");
+        if rows.is_empty() {
+            // reports the record that should have been a row
+            records.next("row")?;
+        }
+        let height = rows.len() + 1;
+        if height > MAX_DEPTH as usize + 3 {
+            let reason = format!("rock deeper than {MAX_DEPTH} is not supported");
+            return Err(Error::parse(1, reason).at_line(rows[0].line()));
+        }
+
+        let mut grid = Vec::new();
+        for r in &rows {
+            let cells = r.text(0)?;
+            if cells.len() != 2 * height {
+                let reason = format!("expected {} cells, found {}", 2 * height, cells.len());
+                return Err(r.error(0, reason));
+            }
+            let row = cells.chars().map(|c| match c {
+                '.' => Ok(Matter::Air),
+                '#' => Ok(Matter::Rock),
+                'o' => Ok(Matter::Sand),
+                _ => Err(r.error(0, format!("expected '.', '#' or 'o', found {c:?}"))),
+            });
+            grid.push(row.collect::<Result<_>>()?);
+        }
+        grid.push(vec![Matter::Rock; 2 * height]);
+
+        Ok(Self {
+            offset: 500 - height as isize,
+            grid,
+        })
+    }
+}
+
 impl Index<(isize, isize)> for Wall {
     type Output = Matter;
 
@@ -146,38 +266,146 @@ impl IndexMut<(isize, isize)> for Wall {
     }
 }
 
-fn solve_1(input: &str) -> usize {
-    Wall::new(input).drop_to_bottom()
-}
+pub struct Dec14;
+
+impl Solution for Dec14 {
+    type Input = Wall;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: Input) -> Result<Self::Input> {
+        let paths: Vec<Polyline> = parse_items(input.lines()?).collect::<Result<_>>()?;
+        Ok(Wall::new(&paths))
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input.clone().drop_to_bottom()
+    }
 
-fn solve_2(input: &str) -> usize {
-    Wall::new(input).drop_to_top()
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        input.clone().drop_to_top()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checkpoint;
+    use crate::prop::{check, expect_eq, ints, vecs};
+    use crate::snapshot::assert_snapshot;
+    use std::collections::HashSet;
 
     #[test]
-    fn example_1() {
-        let input = "src/dec14/example_1.txt";
-        assert_eq!(24, solve_1(input));
+    fn example_2() {
+        let input = Dec14::parse(include_str!("examples/1.txt").into()).unwrap();
+        assert_eq!(93, Dec14::part_2(&input));
     }
 
     #[test]
-    fn puzzle_1() {
-        let input = "src/dec14/input_1.txt";
-        assert_eq!(683, solve_1(input));
+    fn renders() {
+        let wall = Dec14::parse(include_str!("examples/1.txt").into()).unwrap();
+        assert_snapshot("dec14", "wall", &format!("{wall:?}"));
+
+        let mut filled = wall.clone();
+        filled.drop_to_bottom();
+        assert_snapshot("dec14", "wall-part-1", &format!("{filled:?}"));
+
+        let mut filled = wall.clone();
+        filled.drop_to_top();
+        assert_snapshot("dec14", "wall-part-2", &format!("{filled:?}"));
     }
+
     #[test]
-    fn example_2() {
-        let input = "src/dec14/example_1.txt";
-        assert_eq!(93, solve_2(input));
+    fn resumes() {
+        let mut wall = Dec14::parse(include_str!("examples/1.txt").into()).unwrap();
+        assert!(!wall.pour(24, true));
+        let saved = checkpoint::save(&wall);
+        assert_eq!(include_str!("checkpoints/example-unit-24.txt"), saved);
+
+        // the next unit would fall to the floor, so this is the end of part 1
+        let wall: Wall = checkpoint::load(saved.as_str().into()).unwrap();
+        assert_eq!(24, wall.clone().drop_to_bottom());
+        assert_eq!(93, wall.clone().drop_to_top());
+
+        let broken = saved.replacen("row ....", "row ...", 1);
+        let err = checkpoint::load::<Wall>(broken.as_str().into()).err().unwrap();
+        assert_eq!("<input>:2:5: expected 24 cells, found 23", err.to_string());
+    }
+
+    #[test]
+    fn deep() {
+        let wall = Dec14::parse("499,900 -> 501,900".into()).unwrap();
+        let rendered = format!("{wall:?}");
+        assert_eq!(903, rendered.lines().count());
+        assert_eq!(Some(903), rendered.lines().next().unwrap().find('+'));
+        assert_eq!(1, Dec14::part_1(&wall));
+    }
+
+    // Drops sand one unit at a time through a set of blocked points. Without a floor it stops at
+    // the first unit to fall past the lowest rock, with one it stops once the source is buried.
+    fn naive(rocks: &HashSet<(isize, isize)>, floor: bool) -> usize {
+        let max_y = rocks.iter().map(|&(_, y)| y).max().unwrap_or(0);
+        let mut blocked = rocks.clone();
+        let is_blocked = |b: &HashSet<(isize, isize)>, p: (isize, isize)| {
+            b.contains(&p) || (floor && p.1 == max_y + 2)
+        };
+
+        let mut resting = 0;
+        while !blocked.contains(&(500, 0)) {
+            let mut pos = (500, 0);
+            loop {
+                if !floor && pos.1 > max_y {
+                    return resting;
+                }
+                let next = [0, -1, 1]
+                    .map(|dx| (pos.0 + dx, pos.1 + 1))
+                    .into_iter()
+                    .find(|&p| !is_blocked(&blocked, p));
+                match next {
+                    Some(p) => pos = p,
+                    None => break,
+                }
+            }
+            blocked.insert(pos);
+            resting += 1;
+        }
+        resting
     }
 
     #[test]
-    fn puzzle_2() {
-        let input = "src/dec14/input_1.txt";
-        assert_eq!(28821, solve_2(input));
+    fn matches_reference() {
+        let segment = (ints(0u8..=1), ints(-4isize..=4));
+        let path = (ints(490isize..=510), ints(1isize..=12), vecs(segment, 0..=3));
+        check("dec14", vecs(path, 0..=5), |paths| {
+            let mut rocks = HashSet::new();
+            let mut lines = Vec::new();
+            for (x, y, segments) in paths {
+                let mut points = vec![(*x, *y)];
+                for &(vertical, d) in segments {
+                    let (x, y) = *points.last().unwrap();
+                    points.push(match vertical {
+                        0 => (x + d, y),
+                        _ => (x, (y + d).clamp(1, 14)),
+                    });
+                }
+
+                for pair in points.windows(2) {
+                    let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                    for x in x0.min(x1)..=x0.max(x1) {
+                        for y in y0.min(y1)..=y0.max(y1) {
+                            rocks.insert((x, y));
+                        }
+                    }
+                }
+                rocks.insert(points[0]);
+
+                let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+                lines.push(points.join(" -> "));
+            }
+            let wall = Dec14::parse(Input::Str(&lines.join("\n"))).map_err(|e| e.to_string())?;
+
+            expect_eq("part 1", naive(&rocks, false), Dec14::part_1(&wall))?;
+            expect_eq("part 2", naive(&rocks, true), Dec14::part_2(&wall))
+        });
     }
 }
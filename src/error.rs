@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::num::ParseIntError;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        file: Option<String>,
+        source: io::Error,
+    },
+    Parse {
+        file: Option<String>,
+        line: Option<usize>,
+        col: usize,
+        reason: String,
+    },
+    // the input parsed, but has no answer
+    Unsolvable(String),
+}
+
+impl Error {
+    pub fn parse(col: usize, reason: impl Into<String>) -> Self {
+        Self::Parse {
+            file: None,
+            line: None,
+            col,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn unsolvable(reason: impl Into<String>) -> Self {
+        Self::Unsolvable(reason.into())
+    }
+
+    pub fn at_line(mut self, n: usize) -> Self {
+        if let Self::Parse { line, .. } = &mut self {
+            line.get_or_insert(n);
+        }
+        self
+    }
+
+    pub fn in_file(mut self, name: &str) -> Self {
+        match &mut self {
+            Self::Io { file, .. } | Self::Parse { file, .. } => {
+                file.get_or_insert_with(|| name.to_owned());
+            }
+            Self::Unsolvable(_) => {}
+        }
+        self
+    }
+
+    // moves the column of an error raised on a substring to its position in the enclosing string
+    pub fn offset(mut self, by: usize) -> Self {
+        if let Self::Parse { col, .. } = &mut self {
+            *col += by;
+        }
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { file, source } => {
+                if let Some(file) = file {
+                    write!(f, "{file}: ")?;
+                }
+                write!(f, "{source}")
+            }
+            Self::Parse {
+                file,
+                line,
+                col,
+                reason,
+            } => {
+                match (file, line) {
+                    (Some(file), Some(line)) => write!(f, "{file}:{line}:{col}")?,
+                    (Some(file), None) => write!(f, "{file}: column {col}")?,
+                    (None, Some(line)) => write!(f, "line {line}, column {col}")?,
+                    (None, None) => write!(f, "column {col}")?,
+                }
+                write!(f, ": {reason}")
+            }
+            Self::Unsolvable(reason) => write!(f, "no answer: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { .. } | Self::Unsolvable(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Self::Io { file: None, source }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(value: ParseIntError) -> Self {
+        Self::parse(1, value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let err = Error::parse(3, "expected digit").offset(4);
+        assert_eq!("column 7: expected digit", err.to_string());
+
+        let err = err.at_line(2).at_line(5).in_file("src/dec01/input_1.txt");
+        assert_eq!("src/dec01/input_1.txt:2:7: expected digit", err.to_string());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::error::{Error, Result};
+use crate::rng::{fnv, Rng};
+use crate::runner::{catch_quietly, panic_message};
+use crate::solution::Day;
+use crate::utils::Input;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// Mutation-based fuzzing of the day parsers. Starting from each day's examples, inputs are
+// corrupted a few bytes or lines at a time and parsed; a parser may reject them but must never
+// panic. Inputs that still parse join the corpus, so later mutations build on valid structure.
+//
+// Crashing inputs are saved to `src/<day>/crashes/`, where tests/fuzz.rs replays them.
+pub const CRASHES: &str = "crashes";
+
+const MAX_CORPUS: usize = 256;
+
+// bytes that mean something to at least one parser
+const INTERESTING: &[u8] = b"0123456789[],-> \n$/.:+*abcdxyzSE";
+const NUMBERS: &[&str] = &[
+    "0",
+    "1",
+    "-1",
+    "255",
+    "4294967295",
+    "4294967296",
+    "9223372036854775807",
+    "18446744073709551615",
+    "99999999999999999999999",
+];
+
+pub struct Crash {
+    pub input: Vec<u8>,
+    pub message: String,
+}
+
+pub struct Report {
+    pub day: &'static str,
+    pub runs: usize,
+    // one per distinct panic message
+    pub crashes: Vec<Crash>,
+}
+
+// whether `day` accepted `data`, or the panic message if parsing it panicked, which isn't printed
+pub fn try_parse(day: &Day, data: &[u8]) -> Result<bool, String> {
+    catch_quietly(|| day.parse(Input::Bytes(data)).is_ok())
+        .map_err(|payload| panic_message(payload.as_ref()))
+}
+
+pub fn fuzz(day: &'static Day, seeds: &[Vec<u8>], runs: usize, seed: u64) -> Report {
+    let mut rng = Rng::new(seed);
+    let mut corpus: Vec<Vec<u8>> = seeds.to_vec();
+    if corpus.is_empty() {
+        corpus.push(Vec::new());
+    }
+    let mut report = Report {
+        day: day.name,
+        runs: 0,
+        crashes: Vec::new(),
+    };
+
+    // the seeds go through unchanged first
+    for run in 0..seeds.len() + runs {
+        let data = match seeds.get(run) {
+            Some(seed) => seed.clone(),
+            None => {
+                let mut data = rng.pick(&corpus).clone();
+                for _ in 0..=rng.below(4) {
+                    mutate(&mut rng, &mut data, seeds);
+                }
+                data
+            }
+        };
+
+        report.runs += 1;
+        match try_parse(day, &data) {
+            Ok(true) if corpus.len() < MAX_CORPUS => corpus.push(data),
+            Ok(_) => {}
+            Err(message) => {
+                if !report.crashes.iter().any(|c| c.message == message) {
+                    report.crashes.push(Crash {
+                        input: data,
+                        message,
+                    });
+                }
+            }
+        }
+    }
+    report
+}
+
+pub fn mutate(rng: &mut Rng, data: &mut Vec<u8>, seeds: &[Vec<u8>]) {
+    let pos = |rng: &mut Rng, len: usize| rng.below(len as u64 + 1) as usize;
+
+    match rng.below(8) {
+        // overwrite a byte
+        0 if !data.is_empty() => {
+            let at = rng.below(data.len() as u64) as usize;
+            data[at] = match rng.below(2) {
+                0 => *rng.pick(INTERESTING),
+                _ => rng.next_u64() as u8,
+            };
+        }
+        // insert a byte
+        1 => {
+            let at = pos(rng, data.len());
+            data.insert(at, *rng.pick(INTERESTING));
+        }
+        // delete a range
+        2 if !data.is_empty() => {
+            let at = rng.below(data.len() as u64) as usize;
+            let end = (at + 1 + rng.below(8) as usize).min(data.len());
+            data.drain(at..end);
+        }
+        // duplicate a range
+        3 if !data.is_empty() => {
+            let at = rng.below(data.len() as u64) as usize;
+            let end = (at + 1 + rng.below(16) as usize).min(data.len());
+            let chunk = data[at..end].to_vec();
+            let to = pos(rng, data.len());
+            data.splice(to..to, chunk);
+        }
+        // swap a number for an extreme one
+        4 => {
+            let digits: Vec<usize> = (0..data.len())
+                .filter(|&i| data[i].is_ascii_digit())
+                .collect();
+            if digits.is_empty() {
+                return;
+            }
+            let start = *rng.pick(&digits);
+            let end = (start..data.len())
+                .find(|&i| !data[i].is_ascii_digit())
+                .unwrap_or(data.len());
+            let number = rng.pick(NUMBERS).as_bytes().to_vec();
+            data.splice(start..end, number);
+        }
+        // replace a line with one from any seed
+        5 if !seeds.is_empty() => {
+            let donor = rng.pick(seeds);
+            let lines: Vec<&[u8]> = donor.split(|&b| b == b'\n').collect();
+            let line = rng.pick(&lines).to_vec();
+
+            let mut ours: Vec<Vec<u8>> = data.split(|&b| b == b'\n').map(<[u8]>::to_vec).collect();
+            let at = rng.below(ours.len() as u64) as usize;
+            ours[at] = line;
+            *data = ours.join(&b'\n');
+        }
+        // insert a long run of one byte, such as brackets nested past any parser's limit
+        6 => {
+            let at = pos(rng, data.len());
+            let run = vec![*rng.pick(INTERESTING); 1 + rng.below(4096) as usize];
+            data.splice(at..at, run);
+        }
+        // drop a line
+        _ => {
+            let mut lines: Vec<&[u8]> = data.split(|&b| b == b'\n').collect();
+            let at = rng.below(lines.len() as u64) as usize;
+            lines.remove(at);
+            *data = lines.join(&b'\n');
+        }
+    }
+}
+
+fn crash_dir(day: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(day)
+        .join(CRASHES)
+}
+
+// saves under a name derived from the contents, so saving the same crash twice is harmless
+pub fn save(day: &str, crash: &Crash) -> Result<PathBuf> {
+    let dir = crash_dir(day);
+    fs::create_dir_all(&dir).map_err(|e| Error::from(e).in_file(&dir.display().to_string()))?;
+
+    let path = dir.join(format!("{:016x}.txt", fnv(&crash.input)));
+    fs::write(&path, &crash.input)
+        .map_err(|e| Error::from(e).in_file(&path.display().to_string()))?;
+    Ok(path)
+}
+
+// every saved crash for `day`, sorted
+pub fn crash_files(day: &str) -> Result<Vec<PathBuf>> {
+    let dir = crash_dir(day);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::from(e).in_file(&dir.display().to_string())),
+    };
+
+    let mut files = Vec::new();
+    for entry in entries {
+        files.push(entry?.path());
+    }
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,521 @@
+use crate::dec14::MAX_DEPTH;
+use crate::rng::Rng;
+use std::fmt::Write;
+
+// Generators for puzzle inputs of arbitrary size, for stress testing and benchmarking. Each one is
+// seeded, so the same seed and sizes always produce the same input, and everything it produces
+// parses and can be solved.
+pub struct Size {
+    pub name: &'static str,
+    pub default: usize,
+    pub about: &'static str,
+}
+
+type Generate = fn(&mut Rng, &Sizes) -> Result<String, String>;
+
+pub struct Generator {
+    pub day: &'static str,
+    pub sizes: &'static [Size],
+    generate: Generate,
+}
+
+pub static GENERATORS: &[Generator] = &[
+    Generator {
+        day: "dec07",
+        sizes: &[
+            size("dirs", 180, "directories besides the root"),
+            size("files", 300, "files, spread over every directory"),
+            size("depth", 10, "deepest directory nesting"),
+        ],
+        generate: dec07,
+    },
+    Generator {
+        day: "dec08",
+        sizes: &[
+            size("width", 99, "trees per row"),
+            size("height", 99, "rows"),
+        ],
+        generate: dec08,
+    },
+    Generator {
+        day: "dec11",
+        sizes: &[
+            size("monkeys", 8, "monkeys, 2 to 9"),
+            size("items", 4, "average starting items per monkey"),
+        ],
+        generate: dec11,
+    },
+    Generator {
+        day: "dec12",
+        sizes: &[
+            size("width", 162, "squares per row"),
+            size("height", 41, "rows"),
+        ],
+        generate: dec12,
+    },
+    Generator {
+        day: "dec14",
+        sizes: &[
+            size("paths", 80, "rock paths"),
+            size("segments", 5, "most segments in a path"),
+            size(
+                "width",
+                150,
+                "spread of the rock around x=500, at most 1000",
+            ),
+            size("depth", 170, "deepest rock"),
+        ],
+        generate: dec14,
+    },
+];
+
+const fn size(name: &'static str, default: usize, about: &'static str) -> Size {
+    Size {
+        name,
+        default,
+        about,
+    }
+}
+
+pub fn find(day: &str) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|g| g.day == day)
+}
+
+impl Generator {
+    // `overrides` replaces the defaults of the named sizes
+    pub fn generate(&self, seed: u64, overrides: &[(String, usize)]) -> Result<String, String> {
+        let mut values: Vec<(&str, usize)> =
+            self.sizes.iter().map(|s| (s.name, s.default)).collect();
+        for (name, value) in overrides {
+            match values.iter_mut().find(|(n, _)| n == name) {
+                Some((_, v)) => *v = *value,
+                None => {
+                    let known: Vec<&str> = self.sizes.iter().map(|s| s.name).collect();
+                    return Err(format!(
+                        "{} has no size {name:?}, expected one of: {}",
+                        self.day,
+                        known.join(", ")
+                    ));
+                }
+            }
+        }
+        (self.generate)(&mut Rng::new(seed), &Sizes(values))
+    }
+}
+
+struct Sizes<'a>(Vec<(&'a str, usize)>);
+
+impl Sizes<'_> {
+    fn get(&self, name: &str) -> usize {
+        let found = self.0.iter().find(|(n, _)| *n == name);
+        found.expect("size is declared by the generator").1
+    }
+}
+
+// a uniform pick from `lo..=hi`
+fn between(rng: &mut Rng, lo: usize, hi: usize) -> usize {
+    rng.range(lo as i128..=hi as i128) as usize
+}
+
+fn shuffle<T>(rng: &mut Rng, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+}
+
+// A terminal session that visits every directory of a random tree depth first. The total size
+// leaves between 0 and 30M free on the 70M disk, so part 2 has something to delete.
+fn dec07(rng: &mut Rng, sizes: &Sizes) -> Result<String, String> {
+    let (dirs, files, depth) = (sizes.get("dirs"), sizes.get("files"), sizes.get("depth"));
+    if files == 0 {
+        return Err("files: at least one file is needed".to_owned());
+    }
+    if dirs > 0 && depth == 0 {
+        return Err("depth: directories need a depth of at least 1".to_owned());
+    }
+
+    // directory 0 is the root; the rest hang off any directory that isn't already at the limit
+    let mut children: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut depths = vec![0];
+    let mut open = vec![0];
+    for d in 1..=dirs {
+        let parent = *rng.pick(&open);
+        children[parent].push(d);
+        children.push(Vec::new());
+        depths.push(depths[parent] + 1);
+        if depths[d] < depth {
+            open.push(d);
+        }
+    }
+
+    let total = between(rng, 45_000_000, 69_000_000) as u128;
+    let weights: Vec<u128> = (0..files).map(|_| between(rng, 1, 1000) as u128).collect();
+    let sum: u128 = weights.iter().sum();
+    let mut contents: Vec<Vec<(usize, usize)>> = vec![Vec::new(); dirs + 1];
+    for (f, w) in weights.into_iter().enumerate() {
+        let size = (w * total / sum).max(1) as usize;
+        contents[rng.below(dirs as u64 + 1) as usize].push((f, size));
+    }
+
+    const EXTENSIONS: &[&str] = &["txt", "dat", "log", "bin", ""];
+    let mut out = String::from("$ cd /\n");
+    // `None` climbs back out of the directory entered before it
+    let mut stack = vec![Some(0)];
+    while let Some(next) = stack.pop() {
+        let Some(d) = next else {
+            out.push_str("$ cd ..\n");
+            continue;
+        };
+        if d != 0 {
+            writeln!(out, "$ cd d{d}").unwrap();
+        }
+        out.push_str("$ ls\n");
+        for &c in &children[d] {
+            writeln!(out, "dir d{c}").unwrap();
+        }
+        for &(f, size) in &contents[d] {
+            let name = match *rng.pick(EXTENSIONS) {
+                "" => format!("f{f}"),
+                ext => format!("f{f}.{ext}"),
+            };
+            writeln!(out, "{size} {name}").unwrap();
+        }
+
+        if d != 0 {
+            stack.push(None);
+        }
+        stack.extend(children[d].iter().rev().map(|&c| Some(c)));
+    }
+    Ok(out)
+}
+
+// Trees grow taller towards the middle of the forest, like in the real inputs.
+fn dec08(rng: &mut Rng, sizes: &Sizes) -> Result<String, String> {
+    let (width, height) = (sizes.get("width"), sizes.get("height"));
+    if width == 0 || height == 0 {
+        return Err("width and height must be at least 1".to_owned());
+    }
+
+    let half = (width.min(height) / 2).max(1);
+    let mut out = String::with_capacity((width + 1) * height);
+    for r in 0..height {
+        for c in 0..width {
+            let edge = r.min(height - 1 - r).min(c).min(width - 1 - c);
+            let h = (edge * 6 / half + rng.below(4) as usize).min(9);
+            out.push((b'0' + h as u8) as char);
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+const PRIMES: &[usize] = &[2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+#[derive(Copy, Clone)]
+enum Op {
+    Add(usize),
+    Mul(usize),
+    Square,
+}
+
+struct Monkey {
+    items: Vec<usize>,
+    op: Op,
+    divisor: usize,
+    targets: (usize, usize),
+}
+
+// Monkeys with distinct prime divisors. Part 1 divides worry by 3 but never takes it modulo
+// anything, so starting items are only kept if they stay within a usize for its 20 rounds.
+fn dec11(rng: &mut Rng, sizes: &Sizes) -> Result<String, String> {
+    let (monkeys, items) = (sizes.get("monkeys"), sizes.get("items"));
+    // the product of the divisors has to fit in a u32
+    if !(2..=PRIMES.len()).contains(&monkeys) {
+        return Err(format!("monkeys: expected 2 to {}", PRIMES.len()));
+    }
+
+    let mut primes = PRIMES.to_vec();
+    shuffle(rng, &mut primes);
+    let squarer = rng.below(monkeys as u64) as usize;
+    let mut barrel: Vec<Monkey> = (0..monkeys)
+        .map(|m| {
+            let op = match rng.below(3) {
+                _ if m == squarer => Op::Square,
+                0 => Op::Add(between(rng, 1, 8)),
+                _ => Op::Mul(between(rng, 2, 19)),
+            };
+            let mut other = || (m + 1 + rng.below(monkeys as u64 - 1) as usize) % monkeys;
+            let on_true = other();
+            let mut on_false = other();
+            while monkeys > 2 && on_false == on_true {
+                on_false = other();
+            }
+            Monkey {
+                items: Vec::new(),
+                op,
+                divisor: primes[m],
+                targets: (on_true, on_false),
+            }
+        })
+        .collect();
+
+    for m in 0..monkeys {
+        for _ in 0..between(rng, items / 2, items + items / 2) {
+            let worry = (0..100)
+                .map(|_| between(rng, 50, 99))
+                .find(|&w| stays_small(&barrel, m, w));
+            barrel[m].items.extend(worry);
+        }
+    }
+
+    let mut out = String::new();
+    for (idx, m) in barrel.iter().enumerate() {
+        let items: Vec<String> = m.items.iter().map(usize::to_string).collect();
+        let op = match m.op {
+            Op::Add(n) => format!("+ {n}"),
+            Op::Mul(n) => format!("* {n}"),
+            Op::Square => "* old".to_owned(),
+        };
+        if idx > 0 {
+            out.push('\n');
+        }
+        writeln!(out, "Monkey {idx}:").unwrap();
+        writeln!(out, "  Starting items: {}", items.join(", ")).unwrap();
+        writeln!(out, "  Operation: new = old {op}").unwrap();
+        writeln!(out, "  Test: divisible by {}", m.divisor).unwrap();
+        writeln!(out, "    If true: throw to monkey {}", m.targets.0).unwrap();
+        writeln!(out, "    If false: throw to monkey {}", m.targets.1).unwrap();
+    }
+    Ok(out)
+}
+
+// follows one item through part 1, where it moves independently of every other item
+fn stays_small(barrel: &[Monkey], mut at: usize, mut worry: usize) -> bool {
+    let mut round = 0;
+    while round < 20 {
+        let m = &barrel[at];
+        let next = match m.op {
+            Op::Add(n) => worry.checked_add(n),
+            Op::Mul(n) => worry.checked_mul(n),
+            Op::Square => worry.checked_mul(worry),
+        };
+        let Some(next) = next else {
+            return false;
+        };
+        worry = next / 3;
+
+        let to = if worry.is_multiple_of(m.divisor) {
+            m.targets.0
+        } else {
+            m.targets.1
+        };
+        // monkeys later in the round get to it again before the round ends
+        if to < at {
+            round += 1;
+        }
+        at = to;
+    }
+    true
+}
+
+// A mountain rising towards the summit, with a path from the start that never climbs more than
+// one step at a time carved through it.
+fn dec12(rng: &mut Rng, sizes: &Sizes) -> Result<String, String> {
+    let (width, height) = (sizes.get("width"), sizes.get("height"));
+    // the path needs at least 25 steps to climb from a to z
+    if width == 0 || height == 0 || width + height < 27 {
+        return Err("width and height must add up to at least 27".to_owned());
+    }
+
+    let dist =
+        |(r1, c1): (usize, usize), (r2, c2): (usize, usize)| r1.abs_diff(r2) + c1.abs_diff(c2);
+    // the start is on the left edge, in a row far enough from some corner on the right
+    let row = rng.below(height as u64) as usize;
+    let row = if row.max(height - 1 - row) + width > 25 {
+        row
+    } else {
+        0
+    };
+    let start = (row, 0);
+    let far = (
+        if row < height - 1 - row {
+            height - 1
+        } else {
+            0
+        },
+        width - 1,
+    );
+    let end = (0..1000)
+        .map(|_| {
+            let r = rng.below(height as u64) as usize;
+            (r, rng.below(width as u64) as usize)
+        })
+        .find(|&e| dist(start, e) >= 25)
+        .unwrap_or(far);
+
+    let spread = (width + height) / 25 + 1;
+    let mut grid: Vec<Vec<u8>> = (0..height)
+        .map(|r| {
+            (0..width)
+                .map(|c| {
+                    let base = 25usize.saturating_sub(dist((r, c), end) / spread);
+                    (base + rng.below(3) as usize).saturating_sub(1).min(25) as u8
+                })
+                .collect()
+        })
+        .collect();
+
+    // step towards the summit a row or a column at a time, so the path never crosses itself
+    let steps = dist(start, end);
+    let mut at = start;
+    for step in 0..=steps {
+        grid[at.0][at.1] = (step * 25 / steps) as u8;
+        let (dr, dc) = (at.0 != end.0, at.1 != end.1);
+        let down = dr && (!dc || rng.below(2) == 0);
+        if down {
+            at.0 = if at.0 < end.0 { at.0 + 1 } else { at.0 - 1 };
+        } else if dc {
+            at.1 = if at.1 < end.1 { at.1 + 1 } else { at.1 - 1 };
+        }
+    }
+
+    let mut out = String::with_capacity((width + 1) * height);
+    for (r, row) in grid.iter().enumerate() {
+        for (c, &e) in row.iter().enumerate() {
+            out.push(match (r, c) {
+                p if p == start => 'S',
+                p if p == end => 'E',
+                _ => (b'a' + e) as char,
+            });
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+// Paths alternate between horizontal and vertical segments, staying below the sand's source.
+fn dec14(rng: &mut Rng, sizes: &Sizes) -> Result<String, String> {
+    let (paths, segments) = (sizes.get("paths"), sizes.get("segments"));
+    let (width, depth) = (sizes.get("width"), sizes.get("depth"));
+    if !(2..=MAX_DEPTH as usize).contains(&depth) {
+        return Err(format!("depth: expected 2 to {MAX_DEPTH}"));
+    }
+    if width > 1000 {
+        return Err("width: expected at most 1000".to_owned());
+    }
+
+    // like the real inputs, the top tenth is left clear so sand has somewhere to fall
+    let (left, right) = (500 - width / 2, 500 + width / 2);
+    let top = (depth / 10).max(2);
+    let mut out = String::new();
+    for _ in 0..paths {
+        let mut at = (between(rng, left, right), between(rng, top, depth));
+        let mut points = vec![at];
+        let mut across = rng.below(2) == 0;
+        for _ in 0..between(rng, 1, segments.max(1)) {
+            let len = between(rng, 1, 12);
+            let back = rng.below(2) == 0;
+            if across {
+                at.0 = if back {
+                    at.0.saturating_sub(len).max(left)
+                } else {
+                    (at.0 + len).min(right)
+                };
+            } else {
+                at.1 = if back {
+                    at.1.saturating_sub(len).max(top)
+                } else {
+                    (at.1 + len).min(depth)
+                };
+            }
+            points.push(at);
+            across = !across;
+        }
+
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+        writeln!(out, "{}", points.join(" -> ")).unwrap();
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_day, Part};
+
+    fn sizes(pairs: &[(&str, usize)]) -> Vec<(String, usize)> {
+        pairs.iter().map(|&(n, v)| (n.to_owned(), v)).collect()
+    }
+
+    #[test]
+    fn solvable() {
+        for g in GENERATORS {
+            let day = find_day(g.day).unwrap();
+            for seed in 0..5 {
+                let text = g.generate(seed, &[]).unwrap();
+                let parsed = day
+                    .parse(text.as_str().into())
+                    .unwrap_or_else(|e| panic!("{} seed {seed}: {e}\n{text}", g.day));
+                for part in Part::ALL {
+                    day.solve(parsed.as_ref(), part)
+                        .unwrap_or_else(|e| panic!("{} seed {seed} part {part}: {e}", g.day));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        for g in GENERATORS {
+            assert_eq!(g.generate(7, &[]), g.generate(7, &[]), "{}", g.day);
+            assert_ne!(g.generate(7, &[]), g.generate(8, &[]), "{}", g.day);
+        }
+    }
+
+    #[test]
+    fn sizes_apply() {
+        let grid = find("dec08").unwrap();
+        let text = grid
+            .generate(1, &sizes(&[("width", 3), ("height", 2)]))
+            .unwrap();
+        assert_eq!(vec![3, 3], text.lines().map(str::len).collect::<Vec<_>>());
+
+        // too small to have an interior, but still solvable
+        let dec08 = find_day("dec08").unwrap();
+        for (width, height) in [(1, 1), (2, 2), (3, 2), (1, 5)] {
+            let text = grid
+                .generate(1, &sizes(&[("width", width), ("height", height)]))
+                .unwrap();
+            let parsed = dec08.parse(text.as_str().into()).unwrap();
+            assert_eq!("0", dec08.solve(parsed.as_ref(), Part::Two).unwrap());
+        }
+
+        let err = grid.generate(1, &sizes(&[("depth", 3)])).err().unwrap();
+        assert_eq!(
+            "dec08 has no size \"depth\", expected one of: width, height",
+            err
+        );
+        assert!(find("dec12")
+            .unwrap()
+            .generate(1, &sizes(&[("width", 5), ("height", 5)]))
+            .is_err());
+
+        // the smallest mountain that can still be climbed
+        let dec12 = find_day("dec12").unwrap();
+        for seed in 0..20 {
+            let text = find("dec12")
+                .unwrap()
+                .generate(seed, &sizes(&[("width", 20), ("height", 7)]))
+                .unwrap();
+            let parsed = dec12.parse(text.as_str().into()).unwrap();
+            assert!(
+                dec12
+                    .solve(parsed.as_ref(), Part::One)
+                    .unwrap()
+                    .parse::<usize>()
+                    .unwrap()
+                    >= 25
+            );
+        }
+    }
+}
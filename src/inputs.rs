@@ -0,0 +1,133 @@
+use crate::error::{Error, Result};
+use crate::utils::{embedded_paths, Input};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// Each day keeps its inputs in two sets:
+//
+//   src/<day>/examples/<n>.txt    worked examples from the puzzle text
+//   src/<day>/inputs/<user>.txt   each person's puzzle input
+//
+// An input is named by its path within the day, without the extension: `examples/1`,
+// `inputs/alice`. Those names key the answers in the manifest.
+pub const EXAMPLES: &str = "examples";
+pub const INPUTS: &str = "inputs";
+
+// picks whose puzzle input is used by default
+pub const USER_VAR: &str = "AOC_USER";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct InputFile {
+    pub day: String,
+    // `examples/1`, `inputs/alice`
+    pub name: String,
+}
+
+impl InputFile {
+    pub fn new(day: &str, name: &str) -> Self {
+        Self {
+            day: day.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    pub fn is_example(&self) -> bool {
+        self.name.starts_with("examples/")
+    }
+
+    // relative to the crate root
+    pub fn path(&self) -> String {
+        format!("src/{}/{}.txt", self.day, self.name)
+    }
+
+    pub fn input(&self) -> Input<'static> {
+        Input::path(self.path())
+    }
+}
+
+// every example and puzzle input of `day`, examples first
+pub fn list(day: &str) -> Result<Vec<InputFile>> {
+    let mut files = Vec::new();
+
+    let embedded: Vec<&str> = embedded_paths().collect();
+    if embedded.is_empty() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join(day);
+        for set in [EXAMPLES, INPUTS] {
+            let dir = root.join(set);
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(Error::from(e).in_file(&dir.display().to_string())),
+            };
+
+            for entry in entries {
+                let path = entry?.path();
+                if let Some(stem) = path
+                    .extension()
+                    .filter(|e| *e == "txt")
+                    .and(path.file_stem())
+                    .and_then(|s| s.to_str())
+                {
+                    files.push(InputFile::new(day, &format!("{set}/{stem}")));
+                }
+            }
+        }
+    } else {
+        let prefix = format!("src/{day}/");
+        for path in embedded {
+            let name = path
+                .strip_prefix(&prefix)
+                .and_then(|p| p.strip_suffix(".txt"))
+                .filter(|n| n.starts_with("examples/") || n.starts_with("inputs/"));
+            if let Some(name) = name {
+                files.push(InputFile::new(day, name));
+            }
+        }
+    }
+
+    // examples sort before inputs
+    files.sort();
+    Ok(files)
+}
+
+// `name` may be a full input name like `examples/2`, or just a user like `alice`
+pub fn find(day: &str, name: &str) -> Result<Option<InputFile>> {
+    Ok(list(day)?
+        .into_iter()
+        .find(|f| f.name == name || f.name == format!("{INPUTS}/{name}")))
+}
+
+// `$AOC_USER`'s puzzle input if set, otherwise the first puzzle input of the day
+pub fn default(day: &str) -> Result<InputFile> {
+    let mut inputs = list(day)?.into_iter().filter(|f| !f.is_example());
+    let missing = |msg: String| Error::from(io::Error::new(io::ErrorKind::NotFound, msg));
+
+    match env::var(USER_VAR) {
+        Ok(user) => inputs
+            .find(|f| f.name == format!("{INPUTS}/{user}"))
+            .ok_or_else(|| missing(format!("{day} has no input for {USER_VAR}={user}"))),
+        Err(_) => inputs
+            .next()
+            .ok_or_else(|| missing(format!("{day} has no puzzle inputs"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_examples_then_inputs() {
+        let names: Vec<String> = list("dec09").unwrap().into_iter().map(|f| f.name).collect();
+        assert_eq!(vec!["examples/1", "examples/2", "inputs/rodaine"], names);
+
+        let found = find("dec09", "rodaine").unwrap().unwrap();
+        assert_eq!("src/dec09/inputs/rodaine.txt", found.path());
+        assert_eq!(None, find("dec09", "nobody").unwrap());
+        assert!(list("dec99").unwrap().is_empty());
+    }
+}
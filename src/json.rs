@@ -0,0 +1,126 @@
+use std::fmt::{Display, Formatter, Write};
+
+// a JSON document, written compactly by `Display`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Arr(Vec<Json>),
+    // keeps insertion order so output is stable
+    Obj(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn obj<K: Into<String>>(fields: impl IntoIterator<Item = (K, Json)>) -> Self {
+        Self::Obj(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Self::Num(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Self::Num(value as f64)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            // JSON has no representation for NaN or infinities
+            Self::Num(n) if !n.is_finite() => f.write_str("null"),
+            Self::Num(n) => write!(f, "{n}"),
+            Self::Str(s) => write_str(f, s),
+            Self::Arr(items) => {
+                f.write_char('[')?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_char(']')
+            }
+            Self::Obj(fields) => {
+                f.write_char('{')?;
+                for (idx, (k, v)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_str(f, k)?;
+                    write!(f, ":{v}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_str(f: &mut Formatter<'_>, s: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for ch in s.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let doc = Json::obj([
+            ("answer", Json::from("##.\n\"x\"\u{1}")),
+            ("expected", Json::from(None::<String>)),
+            (
+                "items",
+                Json::Arr(vec![1.5.into(), 2usize.into(), true.into()]),
+            ),
+        ]);
+        assert_eq!(
+            r###"{"answer":"##.\n\"x\"\u0001","expected":null,"items":[1.5,2,true]}"###,
+            doc.to_string()
+        );
+    }
+}
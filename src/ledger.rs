@@ -0,0 +1,318 @@
+use crate::error::{Error, Result};
+use crate::solution::Part;
+use crate::utils::{FromLines, Input, Line};
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+// Answers submitted by hand and what the site said about them, one per line:
+//
+//   <day> <part> <input>: <answer> (<verdict>)
+//
+// The ledger is local to each checkout and never leaves it. Answers are a single line, so
+// multi-line ones, like dec10's CRT picture, are neither recorded nor checked; the site wants the
+// letters the picture spells instead.
+pub const PATH: &str = "attempts.txt";
+
+const HEADER: &str = "\
+# Submitted answers and their verdicts, recorded with `aoc attempt`.
+#
+#   <day> <part> <input>: <answer> (<verdict>)
+#
+# <verdict> is too high, too low, wrong or correct.
+";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    TooHigh,
+    TooLow,
+    Wrong,
+    Correct,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::TooHigh => "too high",
+            Self::TooLow => "too low",
+            Self::Wrong => "wrong",
+            Self::Correct => "correct",
+        })
+    }
+}
+
+// accepts `too-high` as well, so verdicts can be passed on the command line unquoted
+impl FromStr for Verdict {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.replace('-', " ").as_str() {
+            "too high" => Ok(Self::TooHigh),
+            "too low" => Ok(Self::TooLow),
+            "wrong" => Ok(Self::Wrong),
+            "correct" => Ok(Self::Correct),
+            _ => Err(Error::parse(
+                1,
+                format!("expected too high, too low, wrong or correct, found {s:?}"),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: String,
+    pub part: Part,
+    pub input: String,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+impl Display for Attempt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}: {} ({})",
+            self.day, self.part, self.input, self.answer, self.verdict
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Ledger {
+    attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    // a missing ledger is an empty one
+    pub fn load() -> Result<Self> {
+        let input = Input::path(PATH);
+        let name = input.name();
+        match input.lines() {
+            Ok(lines) => Self::from_lines(lines).map_err(|e| e.in_file(&name)),
+            Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(e) => Err(e.in_file(&name)),
+        }
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    // appends to the ledger file, creating it with a header if needed
+    pub fn record(&mut self, attempt: Attempt) -> Result<()> {
+        if attempt.answer.contains('\n') {
+            let reason = "multi-line answers can't be recorded, enter what they spell instead";
+            return Err(Error::parse(1, reason));
+        }
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(PATH);
+        let name = path.display().to_string();
+        let write = || -> io::Result<()> {
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            if file.metadata()?.len() == 0 {
+                writeln!(file, "{HEADER}")?;
+            }
+            writeln!(file, "{attempt}")
+        };
+        write().map_err(|e| Error::from(e).in_file(&name))?;
+
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    fn matching<'a>(
+        &'a self,
+        day: &'a str,
+        part: Part,
+        input: &'a str,
+    ) -> impl Iterator<Item = &'a Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.day == day && a.part == part && a.input == input)
+    }
+
+    // the highest answer known to be too low and the lowest known to be too high
+    pub fn bounds(&self, day: &str, part: Part, input: &str) -> (Option<i128>, Option<i128>) {
+        let (mut low, mut high) = (None::<i128>, None::<i128>);
+        for a in self.matching(day, part, input) {
+            let Ok(n) = a.answer.parse::<i128>() else {
+                continue;
+            };
+            match a.verdict {
+                Verdict::TooLow => low = Some(low.map_or(n, |l| l.max(n))),
+                Verdict::TooHigh => high = Some(high.map_or(n, |h| h.min(n))),
+                _ => {}
+            }
+        }
+        (low, high)
+    }
+
+    // reasons to doubt `answer` given what's already been submitted for the same input
+    pub fn check(&self, day: &str, part: Part, input: &str, answer: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if answer.contains('\n') {
+            return warnings;
+        }
+
+        for a in self.matching(day, part, input) {
+            match a.verdict {
+                Verdict::Correct if a.answer != answer => {
+                    warnings.push(format!("differs from the accepted answer {}", a.answer))
+                }
+                Verdict::Correct => {}
+                _ if a.answer == answer => {
+                    warnings.push(format!("{answer} was already submitted: {}", a.verdict))
+                }
+                _ => {}
+            }
+        }
+
+        let Ok(n) = answer.parse::<i128>() else {
+            return warnings;
+        };
+        let (low, high) = self.bounds(day, part, input);
+        match (low, high) {
+            (Some(low), _) if n < low => {
+                warnings.push(format!("{answer} is below {low}, which was too low"))
+            }
+            (_, Some(high)) if n > high => {
+                warnings.push(format!("{answer} is above {high}, which was too high"))
+            }
+            _ => {}
+        }
+        warnings
+    }
+
+    fn attempt(line: &Line) -> Result<Attempt> {
+        let (key, rest) = line
+            .split_once(':')
+            .ok_or_else(|| line.error(line.len() + 1, "expected ':'"))?;
+
+        let fields: Vec<&str> = key.split_whitespace().collect();
+        let [day, part, input] = fields[..] else {
+            return Err(line.error(1, "expected \"<day> <part> <input>: <answer> (<verdict>)\""));
+        };
+
+        let (answer, verdict) = rest
+            .trim()
+            .strip_suffix(')')
+            .and_then(|r| r.rsplit_once(" ("))
+            .ok_or_else(|| line.error(key.len() + 2, "expected \"<answer> (<verdict>)\""))?;
+
+        Ok(Attempt {
+            day: day.to_owned(),
+            part: line.parse_field(part)?,
+            input: input.to_owned(),
+            answer: answer.trim().to_owned(),
+            verdict: line.parse_field(verdict)?,
+        })
+    }
+}
+
+impl FromLines for Ledger {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
+        let mut attempts = Vec::new();
+        for line in lines {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            attempts.push(Self::attempt(&line)?);
+        }
+        Ok(Self { attempts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger(src: &str) -> Ledger {
+        Ledger::from_lines(Input::Str(src).lines().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn bounds() {
+        let l = ledger(
+            "# comment\n\
+             dec01 1 inputs/alice: 100 (too low)\n\
+             dec01 1 inputs/alice: 300 (too high)\n\
+             dec01 1 inputs/alice: 150 (too low)\n\
+             dec01 1 inputs/alice: 200 (wrong)\n\
+             dec01 1 inputs/bob: 10 (too high)\n",
+        );
+
+        assert_eq!(
+            (Some(150), Some(300)),
+            l.bounds("dec01", Part::One, "inputs/alice")
+        );
+        assert!(l
+            .check("dec01", Part::One, "inputs/alice", "250")
+            .is_empty());
+        assert!(l.check("dec01", Part::Two, "inputs/alice", "1").is_empty());
+        assert_eq!(
+            vec!["120 is below 150, which was too low"],
+            l.check("dec01", Part::One, "inputs/alice", "120")
+        );
+        assert_eq!(
+            vec!["350 is above 300, which was too high"],
+            l.check("dec01", Part::One, "inputs/alice", "350")
+        );
+        assert_eq!(
+            vec!["200 was already submitted: wrong"],
+            l.check("dec01", Part::One, "inputs/alice", "200")
+        );
+        assert_eq!(
+            vec!["150 was already submitted: too low"],
+            l.check("dec01", Part::One, "inputs/alice", "150")
+        );
+    }
+
+    #[test]
+    fn correct() {
+        let l = ledger("dec10 2 inputs/alice: EHZFZHCZ (correct)\n");
+        assert!(l
+            .check("dec10", Part::Two, "inputs/alice", "EHZFZHCZ")
+            .is_empty());
+        assert_eq!(
+            vec!["differs from the accepted answer EHZFZHCZ"],
+            l.check("dec10", Part::Two, "inputs/alice", "ABCDEFGH")
+        );
+
+        // the picture the solver draws can't be compared against the letters it spells
+        let picture = "#### #  #\n#    #  #\n";
+        assert!(l
+            .check("dec10", Part::Two, "inputs/alice", picture)
+            .is_empty());
+        let mut l = l;
+        let err = l
+            .record(Attempt {
+                day: "dec10".to_owned(),
+                part: Part::Two,
+                input: "inputs/alice".to_owned(),
+                answer: picture.to_owned(),
+                verdict: Verdict::Wrong,
+            })
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("multi-line"), "{err}");
+        assert_eq!(1, l.attempts().len());
+    }
+
+    #[test]
+    fn errors() {
+        let parse = |src| Ledger::from_lines(Input::Str(src).lines().unwrap());
+
+        let err = parse("dec01 1 inputs/alice: 100 (too warm)").err().unwrap();
+        assert_eq!(
+            "line 1, column 28: expected too high, too low, wrong or correct, found \"too warm\"",
+            err.to_string()
+        );
+        assert!(parse("dec01 1 inputs/alice: 100").is_err());
+        assert_eq!(Ok(Verdict::TooHigh), "too-high".parse().map_err(|_| ()));
+    }
+}
@@ -1,23 +1,57 @@
-#![allow(dead_code)]
-#![feature(test)]
-#![feature(type_alias_impl_trait)]
-#![feature(drain_filter)]
-#![feature(let_chains)]
-#![feature(iter_array_chunks)]
-#![feature(iter_next_chunk)]
+#![cfg_attr(feature = "nightly", feature(iter_array_chunks))]
+#![cfg_attr(feature = "nightly", feature(iter_next_chunk))]
 
-mod dec01;
-mod dec02;
-mod dec03;
-mod dec04;
-mod dec05;
-mod dec06;
-mod dec07;
-mod dec08;
-mod dec09;
-mod dec10;
-mod dec11;
-mod dec12;
-mod dec13;
-mod dec14;
+pub mod alloc;
+pub mod bench;
+pub mod checkpoint;
+mod error;
+pub mod fuzz;
+pub mod gen;
+pub mod inputs;
+pub mod json;
+pub mod ledger;
+pub mod manifest;
+pub mod params;
+#[cfg(test)]
+mod prop;
+pub mod report;
+mod rng;
+pub mod runner;
+#[cfg(test)]
+mod snapshot;
+mod solution;
+pub mod trace;
 mod utils;
+
+pub use error::{Error, Result};
+pub use solution::{Answer, Day, Part, Puzzle, Solution, Variant, DEFAULT_VARIANT};
+pub use utils::{embedded_paths, FromLines, Group, GroupIterator, Input, Line};
+
+macro_rules! days {
+    ($($day:ident::$solution:ident),* $(,)?) => {
+        $(pub mod $day;)*
+
+        pub static DAYS: &[Day] = &[$(Day::new(stringify!($day), &$day::$solution)),*];
+    };
+}
+
+days! {
+    dec01::Dec01,
+    dec02::Dec02,
+    dec03::Dec03,
+    dec04::Dec04,
+    dec05::Dec05,
+    dec06::Dec06,
+    dec07::Dec07,
+    dec08::Dec08,
+    dec09::Dec09,
+    dec10::Dec10,
+    dec11::Dec11,
+    dec12::Dec12,
+    dec13::Dec13,
+    dec14::Dec14,
+}
+
+pub fn find_day(name: &str) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.name == name)
+}
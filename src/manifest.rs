@@ -0,0 +1,149 @@
+use crate::error::{Error, Result};
+use crate::solution::Part;
+use crate::utils::{FromLines, Input, Line};
+use std::io;
+
+pub const PATH: &str = "answers.txt";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: String,
+    pub part: Part,
+    pub input: String,
+    pub answer: String,
+}
+
+impl Entry {
+    pub fn input_path(&self) -> String {
+        format!("src/{}/{}.txt", self.day, self.input)
+    }
+}
+
+// expected answers per day, part and input, read from `answers.txt`, which the `embedded-inputs`
+// feature compiles in along with the inputs
+#[derive(Debug, Default)]
+pub struct Manifest {
+    entries: Vec<Entry>,
+}
+
+impl Manifest {
+    // a missing manifest expects no answers, so every job is solved without being checked
+    pub fn load() -> Result<Self> {
+        let input = Input::path(PATH);
+        let name = input.name();
+        match input.lines() {
+            Ok(lines) => Self::from_lines(lines).map_err(|e| e.in_file(&name)),
+            Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(e) => Err(e.in_file(&name)),
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn expected(&self, day: &str, part: Part, input: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.day == day && e.part == part && e.input == input)
+            .map(|e| e.answer.as_str())
+    }
+
+    fn header(line: &Line) -> Result<Entry> {
+        let (key, answer) = line
+            .split_once(':')
+            .ok_or_else(|| line.error(line.len() + 1, "expected ':'"))?;
+
+        let fields: Vec<&str> = key.split_whitespace().collect();
+        let [day, part, input] = fields[..] else {
+            return Err(line.error(1, "expected \"<day> <part> <input>: <answer>\""));
+        };
+
+        Ok(Entry {
+            day: day.to_owned(),
+            part: line.parse_field(part)?,
+            input: input.to_owned(),
+            answer: answer.trim().to_owned(),
+        })
+    }
+}
+
+impl FromLines for Manifest {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self> {
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut open = false;
+
+        for line in lines {
+            if let Some(cont) = line.strip_prefix("  ") {
+                let entry = entries.last_mut().filter(|_| open).ok_or_else(|| {
+                    line.error(1, "continuation line without a multi-line answer")
+                })?;
+                if !entry.answer.is_empty() {
+                    entry.answer.push('\n');
+                }
+                entry.answer.push_str(cont);
+                continue;
+            }
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                open = false;
+                continue;
+            }
+
+            let entry = Self::header(&line)?;
+            let dup = entries
+                .iter()
+                .any(|e| e.day == entry.day && e.part == entry.part && e.input == entry.input);
+            if dup {
+                return Err(line.error(1, "duplicate entry"));
+            }
+
+            open = entry.answer.is_empty();
+            entries.push(entry);
+        }
+
+        if let Some(e) = entries.iter().find(|e| e.answer.is_empty()) {
+            let reason = format!("{} part {} {} has no answer", e.day, e.part, e.input);
+            return Err(Error::parse(1, reason));
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_line_answers() {
+        let src = "# comment\ndec01 1 examples/1: 24000\ndec10 2 examples/1:\n  ##..\n  .##.\n";
+        let m = Manifest::from_lines(Input::Str(src).lines().unwrap()).unwrap();
+
+        assert_eq!(2, m.entries().len());
+        assert_eq!(Some("24000"), m.expected("dec01", Part::One, "examples/1"));
+        assert_eq!(
+            Some("##..\n.##."),
+            m.expected("dec10", Part::Two, "examples/1")
+        );
+        assert_eq!(None, m.expected("dec10", Part::One, "examples/1"));
+    }
+
+    #[test]
+    fn errors() {
+        let parse = |src| Manifest::from_lines(Input::Str(src).lines().unwrap());
+
+        let err = parse("dec01 3 inputs/alice: 1").err().unwrap();
+        assert_eq!(
+            "line 1, column 7: expected part 1 or 2, found \"3\"",
+            err.to_string()
+        );
+
+        let err = parse("dec01 1 inputs/alice: 1\ndec01 1 inputs/alice: 2")
+            .err()
+            .unwrap();
+        assert_eq!("line 2, column 1: duplicate entry", err.to_string());
+    }
+}
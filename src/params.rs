@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+// Named constants a day's parts depend on, declared by the day with their type and default so
+// the runner can override them, e.g. `--param rounds_2=50000`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Int(usize),
+    // written as a comma-separated list
+    Ints(Cow<'static, [isize]>),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(n) => f.pad(&n.to_string()),
+            Self::Ints(ns) => {
+                let ns: Vec<String> = ns.iter().map(isize::to_string).collect();
+                f.pad(&ns.join(","))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: Value,
+    // smallest allowed value of an `Int`
+    pub min: usize,
+    pub about: &'static str,
+}
+
+impl Param {
+    pub const fn int(name: &'static str, default: usize, about: &'static str) -> Self {
+        Self {
+            name,
+            default: Value::Int(default),
+            min: 0,
+            about,
+        }
+    }
+
+    pub const fn ints(name: &'static str, default: &'static [isize], about: &'static str) -> Self {
+        Self {
+            name,
+            default: Value::Ints(Cow::Borrowed(default)),
+            min: 0,
+            about,
+        }
+    }
+
+    pub const fn at_least(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+
+    fn parse(&self, s: &str) -> Result<Value, String> {
+        let name = self.name;
+        match self.default {
+            Value::Int(_) => {
+                let n: usize = s.parse().map_err(|e| format!("{name}: {e}"))?;
+                if n < self.min {
+                    return Err(format!("{name}: must be at least {}", self.min));
+                }
+                Ok(Value::Int(n))
+            }
+            Value::Ints(_) => {
+                let ns = s
+                    .split(',')
+                    .filter(|n| !n.is_empty())
+                    .map(|n| n.trim().parse().map_err(|e| format!("{name}: {n:?}: {e}")))
+                    .collect::<Result<Vec<isize>, _>>()?;
+                Ok(Value::Ints(Cow::Owned(ns)))
+            }
+        }
+    }
+}
+
+// the values of every param a day declares
+#[derive(Debug, Clone)]
+pub struct Params {
+    values: Vec<(&'static str, Value)>,
+}
+
+impl Params {
+    pub fn defaults(params: &[Param]) -> Self {
+        Self {
+            values: params.iter().map(|p| (p.name, p.default.clone())).collect(),
+        }
+    }
+
+    // the defaults, with `overrides` given as unparsed name and value pairs
+    pub fn with(params: &[Param], overrides: &[(String, String)]) -> Result<Self, String> {
+        let mut out = Self::defaults(params);
+        for (name, value) in overrides {
+            let Some(idx) = params.iter().position(|p| p.name == name) else {
+                let known: Vec<&str> = params.iter().map(|p| p.name).collect();
+                if known.is_empty() {
+                    return Err(format!("{name}: no such param, the day has none"));
+                }
+                let known = known.join(", ");
+                return Err(format!("{name}: no such param, expected one of: {known}"));
+            };
+            out.values[idx].1 = params[idx].parse(value)?;
+        }
+        Ok(out)
+    }
+
+    fn get(&self, name: &str) -> &Value {
+        let found = self.values.iter().find(|(n, _)| *n == name);
+        &found
+            .unwrap_or_else(|| panic!("no param {name:?} declared"))
+            .1
+    }
+
+    pub fn int(&self, name: &str) -> usize {
+        match self.get(name) {
+            Value::Int(n) => *n,
+            v => panic!("param {name:?} is not an int: {v:?}"),
+        }
+    }
+
+    pub fn ints(&self, name: &str) -> &[isize] {
+        match self.get(name) {
+            Value::Ints(ns) => ns,
+            v => panic!("param {name:?} is not a list: {v:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: &[Param] = &[
+        Param::int("rounds", 20, "rounds to play"),
+        Param::int("relief", 3, "worry divisor").at_least(1),
+        Param::ints("captures", &[20, 60], "cycles to sample"),
+    ];
+
+    fn set(overrides: &[(&str, &str)]) -> Result<Params, String> {
+        let overrides: Vec<(String, String)> = overrides
+            .iter()
+            .map(|&(n, v)| (n.to_owned(), v.to_owned()))
+            .collect();
+        Params::with(PARAMS, &overrides)
+    }
+
+    #[test]
+    fn overrides() {
+        let p = set(&[("rounds", "50000"), ("captures", "1,-2,3")]).unwrap();
+        assert_eq!(50000, p.int("rounds"));
+        assert_eq!(3, p.int("relief"));
+        assert_eq!(&[1, -2, 3], p.ints("captures"));
+        assert_eq!("1,-2,3", p.get("captures").to_string());
+
+        let p = Params::defaults(PARAMS);
+        assert_eq!(&[20, 60], p.ints("captures"));
+    }
+
+    #[test]
+    fn errors() {
+        let err = |o| set(o).err().unwrap();
+        assert_eq!("relief: must be at least 1", err(&[("relief", "0")]));
+        assert_eq!(
+            "round: no such param, expected one of: rounds, relief, captures",
+            err(&[("round", "1")])
+        );
+        assert!(err(&[("rounds", "-1")]).starts_with("rounds: "));
+        assert!(err(&[("captures", "1,x")]).starts_with("captures: \"x\": "));
+    }
+}
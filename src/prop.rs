@@ -0,0 +1,286 @@
+use crate::rng::{fnv, Rng};
+use crate::runner::panic_message;
+use std::env;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+// Property testing: `check` runs a property against generated values and, when one fails, shrinks
+// it to a minimal failing case before panicking.
+//
+// Runs are seeded from the property's name so failures reproduce. `AOC_PROP_SEED` overrides the
+// seed and `AOC_PROP_CASES` the number of cases per property.
+const SEED_VAR: &str = "AOC_PROP_SEED";
+const CASES_VAR: &str = "AOC_PROP_CASES";
+const DEFAULT_CASES: usize = 200;
+// bounds the search for a smaller failure, which can't loop but can take a while
+const MAX_SHRINKS: usize = 1000;
+
+pub trait Gen {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    // simpler values to try in place of a failing one, most promising first
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        Vec::new()
+    }
+}
+
+pub struct Ints<T>(RangeInclusive<T>);
+
+// integers in `range`, shrinking towards zero or the end of the range nearest it
+pub fn ints<T>(range: RangeInclusive<T>) -> Ints<T> {
+    Ints(range)
+}
+
+impl<T> Ints<T>
+where
+    T: Copy + TryInto<i128>,
+{
+    fn bounds(&self) -> (i128, i128) {
+        let wide = |n: T| n.try_into().ok().expect("range fits in i128");
+        (wide(*self.0.start()), wide(*self.0.end()))
+    }
+}
+
+impl<T> Gen for Ints<T>
+where
+    T: Copy + Debug + TryInto<i128> + TryFrom<i128>,
+{
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        let (lo, hi) = self.bounds();
+        let n = rng.range(lo..=hi);
+        T::try_from(n).ok().unwrap()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let (lo, hi) = self.bounds();
+        let v = (*value).try_into().ok().unwrap();
+        let target = 0.clamp(lo, hi);
+
+        let mut out: Vec<i128> = Vec::new();
+        for n in [target, target + (v - target) / 2, v - (v - target).signum()] {
+            if n != v && !out.contains(&n) {
+                out.push(n);
+            }
+        }
+        out.into_iter()
+            .map(|n| T::try_from(n).ok().unwrap())
+            .collect()
+    }
+}
+
+pub struct Pick<T: 'static>(&'static [T]);
+
+// one of `items`, shrinking towards the first
+pub fn pick<T>(items: &'static [T]) -> Pick<T> {
+    assert!(!items.is_empty(), "nothing to pick from");
+    Pick(items)
+}
+
+impl<T> Gen for Pick<T>
+where
+    T: Clone + Debug + PartialEq,
+{
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        self.0[rng.below(self.0.len() as u64) as usize].clone()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let idx = self.0.iter().position(|v| v == value).unwrap_or(0);
+        self.0[..idx].to_vec()
+    }
+}
+
+pub struct Vecs<G> {
+    item: G,
+    len: RangeInclusive<usize>,
+}
+
+// vectors with a length in `len`, shrinking by dropping items and then by shrinking them
+pub fn vecs<G: Gen>(item: G, len: RangeInclusive<usize>) -> Vecs<G> {
+    Vecs { item, len }
+}
+
+impl<G: Gen> Gen for Vecs<G> {
+    type Value = Vec<G::Value>;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value {
+        let (lo, hi) = (*self.len.start(), *self.len.end());
+        let len = rng.range(lo as i128..=hi as i128) as usize;
+        (0..len).map(|_| self.item.generate(rng)).collect()
+    }
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+        let min = *self.len.start();
+        let mut out = Vec::new();
+
+        let half = value.len() / 2;
+        if half > 0 && value.len() - half >= min {
+            out.push(value[half..].to_vec());
+            out.push(value[..value.len() - half].to_vec());
+        }
+        if value.len() > min {
+            for idx in 0..value.len() {
+                let mut smaller = value.clone();
+                smaller.remove(idx);
+                out.push(smaller);
+            }
+        }
+
+        for (idx, item) in value.iter().enumerate() {
+            for simpler in self.item.shrink(item) {
+                let mut next = value.clone();
+                next[idx] = simpler;
+                out.push(next);
+            }
+        }
+        out
+    }
+}
+
+macro_rules! tuple_gen {
+    ($($g:ident $idx:tt),+) => {
+        impl<$($g: Gen),+> Gen for ($($g,)+) {
+            type Value = ($($g::Value,)+);
+
+            fn generate(&self, rng: &mut Rng) -> Self::Value {
+                ($(self.$idx.generate(rng),)+)
+            }
+
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut out = Vec::new();
+                $(
+                    for simpler in self.$idx.shrink(&value.$idx) {
+                        let mut next = value.clone();
+                        next.$idx = simpler;
+                        out.push(next);
+                    }
+                )+
+                out
+            }
+        }
+    };
+}
+
+tuple_gen!(A 0, B 1);
+tuple_gen!(A 0, B 1, C 2);
+tuple_gen!(A 0, B 1, C 2, D 3);
+
+// a failed property's explanation, for comparing an answer against a reference
+pub fn expect_eq<T: PartialEq + Debug>(what: &str, expected: T, actual: T) -> Result<(), String> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(format!("{what}: expected {expected:?}, got {actual:?}"))
+    }
+}
+
+// Runs `prop` against values from `gen`, panicking with the smallest failing value found. Panics
+// inside `prop` count as failures.
+pub fn check<G, P>(name: &str, gen: G, prop: P)
+where
+    G: Gen,
+    P: Fn(&G::Value) -> Result<(), String>,
+{
+    let seed = match env::var(SEED_VAR) {
+        Ok(s) => s.parse().expect("AOC_PROP_SEED should be a u64"),
+        Err(_) => fnv(name.as_bytes()),
+    };
+    let cases = env::var(CASES_VAR)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_CASES);
+
+    let run = |value: &G::Value| match catch_unwind(AssertUnwindSafe(|| prop(value))) {
+        Ok(result) => result,
+        Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+    };
+
+    let mut rng = Rng::new(seed);
+    for case in 0..cases {
+        let value = gen.generate(&mut rng);
+        let Err(reason) = run(&value) else {
+            continue;
+        };
+
+        let (minimal, reason, shrinks) = shrink(&gen, value, reason, run);
+        panic!(
+            "property {name} failed on case {case} ({SEED_VAR}={seed}), shrunk {shrinks} times:\n  \
+             input: {minimal:?}\n  {reason}"
+        );
+    }
+}
+
+// greedily takes the first simpler value that still fails until none do
+fn shrink<G, F>(
+    gen: &G,
+    mut value: G::Value,
+    mut reason: String,
+    run: F,
+) -> (G::Value, String, usize)
+where
+    G: Gen,
+    F: Fn(&G::Value) -> Result<(), String>,
+{
+    let mut shrinks = 0;
+    'outer: while shrinks < MAX_SHRINKS {
+        for simpler in gen.shrink(&value) {
+            if let Err(r) = run(&simpler) {
+                (value, reason) = (simpler, r);
+                shrinks += 1;
+                continue 'outer;
+            }
+        }
+        break;
+    }
+    (value, reason, shrinks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        let gen = vecs(ints(-5i32..=5), 0..=8);
+        let a: Vec<_> = (0..20).map(|_| gen.generate(&mut Rng::new(7))).collect();
+        let b: Vec<_> = (0..20).map(|_| gen.generate(&mut Rng::new(7))).collect();
+        assert_eq!(a, b);
+
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let n = ints(3u8..=9).generate(&mut rng);
+            assert!((3..=9).contains(&n));
+        }
+    }
+
+    #[test]
+    fn shrinks_to_minimal() {
+        let result = catch_unwind(|| {
+            check("all below 10", vecs(ints(0u32..=50), 0..=20), |v| {
+                match v.iter().find(|&&n| n >= 10) {
+                    None => Ok(()),
+                    Some(n) => Err(format!("found {n}")),
+                }
+            })
+        });
+
+        let msg = panic_message(result.err().unwrap().as_ref());
+        assert!(msg.contains("input: [10]\n"), "{msg}");
+        assert!(msg.contains("found 10"), "{msg}");
+    }
+
+    #[test]
+    fn shrinks_ints_towards_zero() {
+        assert_eq!(vec![0, 5, 9], ints(0..=20).shrink(&10));
+        assert_eq!(vec![-3, -5, -7], ints(-10..=-3).shrink(&-8));
+        assert!(ints(0..=20).shrink(&0).is_empty());
+        assert_eq!(vec!['a', 'b'], pick(&['a', 'b', 'c']).shrink(&'c'));
+    }
+}
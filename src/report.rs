@@ -0,0 +1,215 @@
+use crate::json::Json;
+use crate::manifest::Manifest;
+use crate::runner::{JobResult, Outcome};
+use crate::solution::Part;
+use std::fmt::Write;
+use std::time::Duration;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail,
+    // solved, but the manifest has no expected answer to check it against
+    Unchecked,
+    // the input failed to parse or the solution panicked
+    Error,
+}
+
+impl Status {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Unchecked => "unchecked",
+            Status::Error => "error",
+        }
+    }
+}
+
+// one day/part run against one input, checked against the manifest
+pub struct Record {
+    pub day: &'static str,
+    pub part: Part,
+    pub input: String,
+    pub answer: Option<String>,
+    pub expected: Option<String>,
+    pub status: Status,
+    // why the job errored, if it did
+    pub message: Option<String>,
+    pub duration: Duration,
+}
+
+impl Record {
+    pub fn new(result: &JobResult, manifest: &Manifest) -> Self {
+        let expected = manifest
+            .expected(result.day, result.part, &result.input)
+            .map(str::to_owned);
+
+        let (answer, message) = match &result.outcome {
+            Outcome::Answer(a) => (Some(a.clone()), None),
+            Outcome::Error(e) => (None, Some(e.clone())),
+            Outcome::Panic(msg) => (None, Some(format!("panicked: {msg}"))),
+            Outcome::Mismatch(msg) => (None, Some(msg.clone())),
+        };
+
+        let status = match (&answer, &expected) {
+            _ if matches!(result.outcome, Outcome::Mismatch(_)) => Status::Fail,
+            (None, _) => Status::Error,
+            (Some(_), None) => Status::Unchecked,
+            (Some(a), Some(e)) if a == e => Status::Pass,
+            (Some(_), Some(_)) => Status::Fail,
+        };
+
+        Self {
+            day: result.day,
+            part: result.part,
+            input: result.input.clone(),
+            answer,
+            expected,
+            status,
+            message,
+            duration: result.elapsed,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        matches!(self.status, Status::Pass | Status::Unchecked)
+    }
+}
+
+pub fn json(records: &[Record]) -> String {
+    let records = records.iter().map(|r| {
+        Json::obj([
+            ("day", r.day.into()),
+            ("part", r.part.to_string().into()),
+            ("input", r.input.as_str().into()),
+            ("answer", r.answer.clone().into()),
+            ("expected", r.expected.clone().into()),
+            ("status", r.status.as_str().into()),
+            ("passed", (r.status == Status::Pass).into()),
+            ("message", r.message.clone().into()),
+            ("duration_ms", (r.duration.as_secs_f64() * 1000.0).into()),
+        ])
+    });
+
+    Json::Arr(records.collect()).to_string() + "\n"
+}
+
+// one suite, with a test case per record grouped into classes by day
+pub fn junit(records: &[Record]) -> String {
+    let count = |s: Status| records.iter().filter(|r| r.status == s).count();
+    let total: Duration = records.iter().map(|r| r.duration).sum();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        out,
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.6}\">",
+        env!("CARGO_PKG_NAME"),
+        records.len(),
+        count(Status::Fail),
+        count(Status::Error),
+        count(Status::Unchecked),
+        total.as_secs_f64(),
+    )
+    .unwrap();
+
+    for r in records {
+        write!(
+            out,
+            "  <testcase classname=\"{}\" name=\"part {} ({})\" time=\"{:.6}\">",
+            xml(r.day),
+            r.part,
+            xml(&r.input),
+            r.duration.as_secs_f64(),
+        )
+        .unwrap();
+
+        let answer = r.answer.as_deref().unwrap_or_default();
+        let expected = r.expected.as_deref().unwrap_or_default();
+        let message = r.message.as_deref().unwrap_or_default();
+        match r.status {
+            Status::Pass => {}
+            Status::Fail if r.message.is_some() => {
+                write!(out, "\n    <failure message=\"{}\"/>\n  ", xml(message)).unwrap()
+            }
+            Status::Fail => write!(
+                out,
+                "\n    <failure message=\"answer does not match\">expected:\n{}\ngot:\n{}</failure>\n  ",
+                xml(expected),
+                xml(answer),
+            )
+            .unwrap(),
+            Status::Unchecked => out += "\n    <skipped message=\"no expected answer\"/>\n  ",
+            Status::Error => write!(out, "\n    <error message=\"{}\"/>\n  ", xml(message)).unwrap(),
+        }
+        out += "</testcase>\n";
+    }
+
+    out + "</testsuite>\n"
+}
+
+fn xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{FromLines, Input};
+
+    fn job(day: &'static str, part: Part, outcome: Outcome) -> JobResult {
+        JobResult {
+            day,
+            part,
+            input: "inputs/alice".to_owned(),
+            outcome,
+            elapsed: Duration::from_millis(2),
+            alloc: None,
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let text = "dec01 1 inputs/alice: 7\ndec01 2 inputs/alice: 8\ndec02 2 inputs/alice: 3\n";
+        let manifest = Manifest::from_lines(Input::Str(text).lines().unwrap()).unwrap();
+        let records: Vec<Record> = [
+            job("dec01", Part::One, Outcome::Answer("7".to_owned())),
+            job("dec01", Part::Two, Outcome::Answer("9".to_owned())),
+            job("dec02", Part::One, Outcome::Answer("1".to_owned())),
+            job("dec02", Part::Two, Outcome::Panic("oops".to_owned())),
+        ]
+        .iter()
+        .map(|j| Record::new(j, &manifest))
+        .collect();
+
+        let statuses: Vec<Status> = records.iter().map(|r| r.status).collect();
+        use Status::*;
+        assert_eq!(vec![Pass, Fail, Unchecked, Error], statuses);
+
+        let json = json(&records[1..2]);
+        assert_eq!(
+            "[{\"day\":\"dec01\",\"part\":\"2\",\"input\":\"inputs/alice\",\"answer\":\"9\",\
+             \"expected\":\"8\",\"status\":\"fail\",\"passed\":false,\"message\":null,\
+             \"duration_ms\":2}]\n",
+            json
+        );
+
+        let xml = junit(&records);
+        assert!(xml.contains("tests=\"4\" failures=\"1\" errors=\"1\" skipped=\"1\""));
+        assert!(xml.contains(
+            "<testcase classname=\"dec01\" name=\"part 1 (inputs/alice)\" time=\"0.002000\"></testcase>"
+        ));
+        assert!(xml.contains("<error message=\"panicked: oops\"/>"));
+    }
+}
@@ -0,0 +1,45 @@
+use std::ops::RangeInclusive;
+
+// splitmix64
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in `0..n`
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i128>) -> i128 {
+        let (lo, hi) = range.into_inner();
+        assert!(lo <= hi, "empty range");
+        let span = (hi - lo) as u128 + 1;
+        match u64::try_from(span) {
+            Ok(span) => lo + self.below(span) as i128,
+            Err(_) => lo + (self.next_u64() as i128),
+        }
+    }
+}
+
+// FNV-1a, a stable hash for deriving seeds and file names from bytes
+pub fn fnv(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,336 @@
+use crate::alloc::{self, fmt_bytes, AllocStats};
+use crate::error::Error;
+use crate::solution::{Day, Part};
+use crate::utils::Input;
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub enum Outcome {
+    Answer(String),
+    // the input couldn't be loaded or parsed
+    Error(String),
+    Panic(String),
+    // a variant of the part disagreed with the default implementation
+    Mismatch(String),
+}
+
+// one part of one day, run against one of its named inputs
+pub struct Job<'a> {
+    pub day: &'a Day,
+    pub part: Part,
+    pub input: String,
+}
+
+// every part of every day against each input `inputs` names for it
+pub fn jobs<'a, F>(days: &'a [Day], inputs: F) -> Vec<Job<'a>>
+where
+    F: Fn(&Day) -> Vec<String>,
+{
+    let mut jobs = Vec::new();
+    for day in days {
+        for input in inputs(day) {
+            for part in Part::ALL {
+                let input = input.clone();
+                jobs.push(Job { day, part, input });
+            }
+        }
+    }
+    jobs
+}
+
+pub struct JobResult {
+    pub day: &'static str,
+    pub part: Part,
+    pub input: String,
+    pub outcome: Outcome,
+    // includes loading and parsing the input
+    pub elapsed: Duration,
+    // only with the `alloc-stats` feature
+    pub alloc: Option<AllocStats>,
+}
+
+impl JobResult {
+    pub fn is_ok(&self) -> bool {
+        matches!(self.outcome, Outcome::Answer(_))
+    }
+}
+
+// Runs `jobs` on up to `threads` workers, isolating panics to the job that raised them and keeping
+// the panic hook from printing them, since they're reported with the job's result. `load`
+// opens a job's input by name on the worker, so each job loads and parses its own copy. Results
+// come back in the order of `jobs` regardless of which finished first.
+pub fn run_all<F>(jobs: &[Job], threads: usize, load: F) -> Vec<JobResult>
+where
+    F: Fn(&Day, &str) -> Input<'static> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(jobs.len()));
+
+    thread::scope(|s| {
+        for _ in 0..threads.clamp(1, jobs.len().max(1)) {
+            s.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(idx) else {
+                    break;
+                };
+                let result = run_job(job, &load);
+                results.lock().unwrap().push((idx, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|&(idx, _)| idx);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+fn run_job<F>(job: &Job, load: &F) -> JobResult
+where
+    F: Fn(&Day, &str) -> Input<'static>,
+{
+    let (day, part) = (job.day, job.part);
+    let start = Instant::now();
+    let (result, alloc) = alloc::measure(|| {
+        catch_quietly(|| {
+            let parsed = day.parse(load(day, &job.input))?;
+            Ok::<_, Error>((day.solve(parsed.as_ref(), part)?, parsed))
+        })
+    });
+    let elapsed = start.elapsed();
+
+    // variants are checked after timing, so `elapsed` and `alloc` cover the default alone
+    let outcome = match result {
+        Ok(Ok((answer, parsed))) => check_variants(day, part, parsed.as_ref(), answer),
+        Ok(Err(e)) => Outcome::Error(e.to_string()),
+        Err(payload) => Outcome::Panic(panic_message(payload.as_ref())),
+    };
+
+    JobResult {
+        day: day.name,
+        part,
+        input: job.input.clone(),
+        outcome,
+        elapsed,
+        alloc,
+    }
+}
+
+fn check_variants(day: &Day, part: Part, parsed: &dyn Any, answer: String) -> Outcome {
+    for variant in day.variants(part).into_iter().skip(1) {
+        let solved = catch_quietly(|| day.solve_variant(parsed, part, variant).unwrap());
+
+        match solved {
+            Ok(Ok(other)) if other == answer => {}
+            Ok(Err(e)) => return Outcome::Error(format!("variant {variant}: {e}")),
+            Ok(Ok(other)) => {
+                let msg = format!("variant {variant} answered {other:?}, expected {answer:?}");
+                return Outcome::Mismatch(msg);
+            }
+            Err(payload) => {
+                let msg = panic_message(payload.as_ref());
+                return Outcome::Panic(format!("variant {variant}: {msg}"));
+            }
+        }
+    }
+
+    Outcome::Answer(answer)
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// `catch_unwind` without the panic hook printing the panic. The hook is shared by every thread,
+// so rather than swapping it out, which would also silence panics elsewhere, it's wrapped once to
+// skip panics on threads that are catching them quietly.
+pub(crate) fn catch_quietly<T>(f: impl FnOnce() -> T) -> thread::Result<T> {
+    static WRAP: Once = Once::new();
+    WRAP.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                hook(info)
+            }
+        }));
+    });
+
+    let was = QUIET.with(|q| q.replace(true));
+    let result = catch_unwind(AssertUnwindSafe(f));
+    QUIET.with(|q| q.set(was));
+    result
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panicked".to_owned()
+    }
+}
+
+// multi-line answers don't fit in a cell, so they're listed in full after the table
+pub fn table(results: &[JobResult]) -> String {
+    let with_alloc = results.iter().any(|r| r.alloc.is_some());
+    let width = results
+        .iter()
+        .map(|r| r.input.len())
+        .max()
+        .unwrap_or(0)
+        .max(5)
+        + 2;
+    let mut out = format!("{:<8}{:<width$}{:<6}{:>12}", "day", "input", "part", "time");
+    if with_alloc {
+        out += &format!("{:>10}{:>12}{:>12}", "allocs", "bytes", "peak");
+    }
+    out += "  result\n";
+    let mut blocks = String::new();
+
+    for r in results {
+        let result = match &r.outcome {
+            Outcome::Answer(a) if a.contains('\n') => {
+                blocks += &format!("\n{} {} part {}:\n{a}\n", r.day, r.input, r.part);
+                "(see below)".to_owned()
+            }
+            Outcome::Answer(a) => a.clone(),
+            Outcome::Error(e) => format!("error: {e}"),
+            Outcome::Panic(msg) => format!("panic: {msg}"),
+            Outcome::Mismatch(msg) => format!("mismatch: {msg}"),
+        };
+
+        out += &format!(
+            "{:<8}{:<width$}{:<6}{:>12}",
+            r.day,
+            r.input,
+            r.part,
+            format!("{:.2?}", r.elapsed),
+        );
+        if with_alloc {
+            let a = r.alloc.unwrap_or_default();
+            out += &format!(
+                "{:>10}{:>12}{:>12}",
+                a.allocs,
+                fmt_bytes(a.bytes),
+                fmt_bytes(a.peak)
+            );
+        }
+        out += &format!("  {result}\n");
+    }
+
+    let failed = results.iter().filter(|r| !r.is_ok()).count();
+    out += &format!("\n{} jobs, {failed} failed\n", results.len());
+    out + &blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Result;
+    use crate::{Solution, Variant};
+
+    struct Flaky;
+
+    impl Solution for Flaky {
+        type Input = usize;
+        type Output1 = usize;
+        type Output2 = usize;
+
+        const VARIANTS_1: &'static [Variant<usize, usize>] = &[("same", |n, _| n + 1)];
+
+        fn parse(input: Input) -> Result<Self::Input> {
+            Ok(input.text()?.trim().parse()?)
+        }
+
+        fn part_1(input: &Self::Input) -> Self::Output1 {
+            input + 1
+        }
+
+        fn part_2(_input: &Self::Input) -> Self::Output2 {
+            panic!("part 2 is broken")
+        }
+    }
+
+    struct Split;
+
+    impl Solution for Split {
+        type Input = usize;
+        type Output1 = usize;
+        type Output2 = usize;
+
+        const VARIANTS_1: &'static [Variant<usize, usize>] = &[("double", |n, _| n * 2)];
+
+        fn parse(input: Input) -> Result<Self::Input> {
+            Flaky::parse(input)
+        }
+
+        fn part_1(input: &Self::Input) -> Self::Output1 {
+            *input
+        }
+
+        fn part_2(input: &Self::Input) -> Self::Output2 {
+            *input
+        }
+    }
+
+    static DAYS: &[Day] = &[
+        Day::new("flaky", &Flaky),
+        Day::new("bad", &Flaky),
+        Day::new("split", &Split),
+    ];
+
+    #[test]
+    fn catches_quietly() {
+        let err = catch_quietly(|| panic!("quiet")).err().unwrap();
+        assert_eq!("quiet", panic_message(err.as_ref()));
+        assert_eq!(3, catch_quietly(|| 3).unwrap());
+        assert!(!QUIET.with(Cell::get));
+    }
+
+    #[test]
+    fn isolates_failures() {
+        // each day's only input is named after its contents
+        let jobs = jobs(DAYS, |d| {
+            vec![if d.name == "bad" { "x" } else { "41" }.to_owned()]
+        });
+        let results = run_all(&jobs, 4, |_, name| {
+            Input::Str(if name == "x" { "x" } else { "41" })
+        });
+
+        let summary: Vec<(&str, Part, String)> = results
+            .iter()
+            .map(|r| {
+                let outcome = match &r.outcome {
+                    Outcome::Answer(a) => a.clone(),
+                    Outcome::Error(e) => format!("error: {e}"),
+                    Outcome::Panic(msg) => format!("panic: {msg}"),
+                    Outcome::Mismatch(msg) => format!("mismatch: {msg}"),
+                };
+                (r.day, r.part, outcome)
+            })
+            .collect();
+
+        let invalid = "error: <input>: column 1: invalid digit found in string".to_owned();
+        assert_eq!(
+            vec![
+                ("flaky", Part::One, "42".to_owned()),
+                ("flaky", Part::Two, "panic: part 2 is broken".to_owned()),
+                ("bad", Part::One, invalid.clone()),
+                ("bad", Part::Two, invalid),
+                (
+                    "split",
+                    Part::One,
+                    "mismatch: variant double answered \"82\", expected \"41\"".to_owned(),
+                ),
+                ("split", Part::Two, "41".to_owned()),
+            ],
+            summary
+        );
+        assert!(table(&results).contains("6 jobs, 4 failed"));
+    }
+}
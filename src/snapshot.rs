@@ -0,0 +1,159 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Snapshot testing: rendered output is compared against a file under `src/<day>/snapshots/`, and
+// a mismatch fails with a line diff. Setting `AOC_ACCEPT_SNAPSHOTS=1` writes the new output over
+// any missing or mismatched snapshot instead; review the changes with git before committing.
+const ACCEPT_VAR: &str = "AOC_ACCEPT_SNAPSHOTS";
+const SNAPSHOTS: &str = "snapshots";
+
+// unchanged lines shown around each change in a diff
+const CONTEXT: usize = 3;
+
+fn path(day: &str, name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(day)
+        .join(SNAPSHOTS)
+        .join(format!("{name}.txt"))
+}
+
+// Panics unless `actual` matches the snapshot `name` of `day`.
+pub fn assert_snapshot(day: &str, name: &str, actual: &str) {
+    let accept = env::var(ACCEPT_VAR).is_ok_and(|v| !v.is_empty() && v != "0");
+    if let Err(msg) = compare(&path(day, name), actual, accept) {
+        panic!("snapshot {day}/{name}: {msg}");
+    }
+}
+
+// Files end in a newline, whether or not the output does, and lines never end in whitespace, which
+// editors tend to strip.
+fn normalize(text: &str) -> String {
+    let mut out = String::new();
+    for line in text.lines() {
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    if out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+fn compare(path: &Path, actual: &str, accept: bool) -> Result<(), String> {
+    let actual = normalize(actual);
+    let expected = match fs::read_to_string(path).map(|e| normalize(&e)) {
+        Ok(expected) if expected == actual => return Ok(()),
+        Ok(expected) => Some(expected),
+        Err(_) => None,
+    };
+    if accept {
+        let write = || {
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, &actual)
+        };
+        return write().map_err(|e| format!("{}: {e}", path.display()));
+    }
+
+    Err(match expected {
+        None => format!(
+            "{} doesn't exist, rerun with {ACCEPT_VAR}=1 to record:\n{actual}",
+            path.display()
+        ),
+        Some(expected) => format!(
+            "doesn't match {}, rerun with {ACCEPT_VAR}=1 to accept:\n{}",
+            path.display(),
+            diff(&expected, &actual)
+        ),
+    })
+}
+
+// A line diff from `old` to `new`, marking removed lines with `-` and added ones with `+`. Runs
+// of unchanged lines far from any change are elided.
+pub fn diff(old: &str, new: &str) -> String {
+    let (a, b): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+
+    // lcs[i][j] is the length of the longest common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<(char, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push((' ', a[i]));
+            (i, j) = (i + 1, j + 1);
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', a[i]));
+            i += 1;
+        } else {
+            lines.push(('+', b[j]));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = (0..lines.len()).filter(|&n| lines[n].0 != ' ').collect();
+    let near = |n: usize| changed.iter().any(|&c| c.abs_diff(n) <= CONTEXT);
+    let mut out = String::new();
+    let mut elided = false;
+    for (n, (mark, line)) in lines.into_iter().enumerate() {
+        if near(n) {
+            out.push_str(&format!("{mark} {line}\n"));
+            elided = false;
+        } else if !elided {
+            out.push_str("  ...\n");
+            elided = true;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let new = "a\nb\nc\nd\ne\nf\nG\nh\ni\nj\n";
+        assert_eq!(
+            "  ...\n  d\n  e\n  f\n- g\n+ G\n  h\n  i\n+ j\n",
+            diff(old, new)
+        );
+        assert_eq!("  ...\n", diff(old, old));
+    }
+
+    #[test]
+    fn accepts() {
+        let dir = env::temp_dir().join(format!("aoc-snapshot-{}", std::process::id()));
+        let path = dir.join("render.txt");
+
+        let missing = compare(&path, "#.#", false).unwrap_err();
+        assert!(missing.contains("doesn't exist"), "{missing}");
+        compare(&path, "#.#", true).unwrap();
+        assert_eq!("#.#\n", fs::read_to_string(&path).unwrap());
+        compare(&path, "#.#\n", false).unwrap();
+
+        let mismatch = compare(&path, ".#.", false).unwrap_err();
+        assert!(mismatch.ends_with("- #.#\n+ .#.\n"), "{mismatch}");
+        compare(&path, ".#.", true).unwrap();
+        compare(&path, ".#.", false).unwrap();
+
+        compare(&path, "#  \n.#. \n", true).unwrap();
+        assert_eq!("#\n.#.\n", fs::read_to_string(&path).unwrap());
+        compare(&path, "#\n.#.", false).unwrap();
+        fs::write(&path, "# \n.#.\n").unwrap();
+        compare(&path, "#\n.#.  ", false).unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
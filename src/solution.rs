@@ -0,0 +1,259 @@
+use crate::error::{Error, Result};
+use crate::params::{Param, Params};
+use crate::utils::Input;
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(Error::parse(
+                1,
+                format!("expected part 1 or 2, found {s:?}"),
+            )),
+        }
+    }
+}
+
+// name given to `part_1`/`part_2` when listing a day's variants
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// What a part returns: a value to print, or a `Result` for parts some inputs have no answer to.
+pub trait Answer: 'static {
+    fn into_answer(self) -> Result<String>;
+}
+
+macro_rules! answers {
+    ($($t:ty),*) => {
+        $(impl Answer for $t {
+            fn into_answer(self) -> Result<String> {
+                Ok(self.to_string())
+            }
+        })*
+    };
+}
+
+answers!(usize, isize, u32, String);
+
+impl<T: Answer> Answer for Result<T> {
+    fn into_answer(self) -> Result<String> {
+        self?.into_answer()
+    }
+}
+
+/// A named alternative implementation of one part.
+pub type Variant<I, O> = (&'static str, fn(&I, &Params) -> O);
+
+/// A single day's puzzle: parse the input once, then answer either part from it.
+pub trait Solution {
+    type Input: 'static;
+    type Output1: Answer;
+    type Output2: Answer;
+
+    /// Other approaches to each part, expected to agree with `part_1` and `part_2`.
+    const VARIANTS_1: &'static [Variant<Self::Input, Self::Output1>] = &[];
+    const VARIANTS_2: &'static [Variant<Self::Input, Self::Output2>] = &[];
+
+    /// Constants the parts depend on, which the runner can override.
+    const PARAMS: &'static [Param] = &[];
+
+    fn parse(input: Input) -> Result<Self::Input>;
+
+    fn part_1(input: &Self::Input) -> Self::Output1;
+
+    fn part_2(input: &Self::Input) -> Self::Output2;
+
+    /// `part_1` with the given values for `PARAMS`; days that declare any override this and
+    /// have `part_1` pass it the defaults.
+    fn part_1_with(input: &Self::Input, _params: &Params) -> Self::Output1 {
+        Self::part_1(input)
+    }
+
+    fn part_2_with(input: &Self::Input, _params: &Params) -> Self::Output2 {
+        Self::part_2(input)
+    }
+
+    /// The working behind the answer to `part`, one fact per line, for days that can show it.
+    fn explain(_input: &Self::Input, _part: Part, _params: &Params) -> Option<String> {
+        None
+    }
+}
+
+/// Object-safe view of a [`Solution`], so days with different input and output
+/// types can live side by side in the registry.
+pub trait Puzzle: Sync {
+    fn parse(&self, input: Input) -> Result<Box<dyn Any>>;
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<String>;
+
+    // names of every implementation of `part`, starting with `DEFAULT_VARIANT`
+    fn variants(&self, part: Part) -> Vec<&'static str>;
+
+    // `None` if the part has no variant by that name
+    fn solve_variant(&self, input: &dyn Any, part: Part, variant: &str) -> Option<Result<String>>;
+
+    fn params(&self) -> &'static [Param];
+
+    // like `solve_variant`, with `params` built from `params()`
+    fn solve_with(
+        &self,
+        input: &dyn Any,
+        part: Part,
+        variant: &str,
+        params: &Params,
+    ) -> Option<Result<String>>;
+
+    // `None` if the day can't explain its answers
+    fn explain(&self, input: &dyn Any, part: Part, params: &Params) -> Option<String>;
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn parse(&self, input: Input) -> Result<Box<dyn Any>> {
+        let name = input.name();
+        match S::parse(input) {
+            Ok(parsed) => Ok(Box::new(parsed)),
+            Err(e) => Err(e.in_file(&name)),
+        }
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<String> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was parsed by a different day");
+
+        match part {
+            Part::One => S::part_1(input).into_answer(),
+            Part::Two => S::part_2(input).into_answer(),
+        }
+    }
+
+    fn variants(&self, part: Part) -> Vec<&'static str> {
+        let names = match part {
+            Part::One => S::VARIANTS_1.iter().map(|v| v.0).collect::<Vec<_>>(),
+            Part::Two => S::VARIANTS_2.iter().map(|v| v.0).collect(),
+        };
+        [DEFAULT_VARIANT].into_iter().chain(names).collect()
+    }
+
+    fn solve_variant(&self, input: &dyn Any, part: Part, variant: &str) -> Option<Result<String>> {
+        if variant == DEFAULT_VARIANT {
+            return Some(self.solve(input, part));
+        }
+        self.solve_with(input, part, variant, &Params::defaults(S::PARAMS))
+    }
+
+    fn params(&self) -> &'static [Param] {
+        S::PARAMS
+    }
+
+    fn solve_with(
+        &self,
+        input: &dyn Any,
+        part: Part,
+        variant: &str,
+        params: &Params,
+    ) -> Option<Result<String>> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was parsed by a different day");
+
+        match (part, variant) {
+            (Part::One, DEFAULT_VARIANT) => Some(S::part_1_with(input, params).into_answer()),
+            (Part::Two, DEFAULT_VARIANT) => Some(S::part_2_with(input, params).into_answer()),
+            (Part::One, _) => S::VARIANTS_1
+                .iter()
+                .find(|v| v.0 == variant)
+                .map(|v| v.1(input, params).into_answer()),
+            (Part::Two, _) => S::VARIANTS_2
+                .iter()
+                .find(|v| v.0 == variant)
+                .map(|v| v.1(input, params).into_answer()),
+        }
+    }
+
+    fn explain(&self, input: &dyn Any, part: Part, params: &Params) -> Option<String> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was parsed by a different day");
+        S::explain(input, part, params)
+    }
+}
+
+pub struct Day {
+    pub name: &'static str,
+    puzzle: &'static dyn Puzzle,
+}
+
+impl Day {
+    pub const fn new(name: &'static str, puzzle: &'static dyn Puzzle) -> Self {
+        Self { name, puzzle }
+    }
+
+    pub fn parse(&self, input: Input) -> Result<Box<dyn Any>> {
+        self.puzzle.parse(input)
+    }
+
+    pub fn solve(&self, input: &dyn Any, part: Part) -> Result<String> {
+        self.puzzle.solve(input, part)
+    }
+
+    pub fn variants(&self, part: Part) -> Vec<&'static str> {
+        self.puzzle.variants(part)
+    }
+
+    pub fn solve_variant(
+        &self,
+        input: &dyn Any,
+        part: Part,
+        variant: &str,
+    ) -> Option<Result<String>> {
+        self.puzzle.solve_variant(input, part, variant)
+    }
+
+    pub fn params(&self) -> &'static [Param] {
+        self.puzzle.params()
+    }
+
+    pub fn solve_with(
+        &self,
+        input: &dyn Any,
+        part: Part,
+        variant: &str,
+        params: &Params,
+    ) -> Option<Result<String>> {
+        self.puzzle.solve_with(input, part, variant, params)
+    }
+
+    pub fn explain(&self, input: &dyn Any, part: Part, params: &Params) -> Option<String> {
+        self.puzzle.explain(input, part, params)
+    }
+
+    pub fn run(&self, input: Input, part: Part) -> Result<String> {
+        let parsed = self.parse(input)?;
+        self.solve(parsed.as_ref(), part)
+    }
+}
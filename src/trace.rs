@@ -0,0 +1,266 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Execution traces: simulations emit events with `event!`, which cost a single atomic load unless
+// the current thread is inside `record`. A trace is one event per line:
+//
+//   <name> <key>=<value> <key>=<value>...
+//
+// Values are written with `Display` and must not contain whitespace.
+thread_local! {
+    static SINK: RefCell<Option<Sink>> = const { RefCell::new(None) };
+}
+
+// threads currently recording, so everyone else can skip the thread-local lookup
+static RECORDING: AtomicUsize = AtomicUsize::new(0);
+
+struct Sink {
+    out: BufWriter<Box<dyn Write>>,
+    // the first write error, reported once recording ends
+    err: Option<io::Error>,
+}
+
+// emits an event if the current thread is recording, e.g. `event!("cycle", n = self.cycle)`
+macro_rules! event {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled() {
+            $crate::trace::emit(
+                $name,
+                &[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*],
+            );
+        }
+    };
+}
+pub(crate) use event;
+
+#[inline]
+pub fn enabled() -> bool {
+    RECORDING.load(Ordering::Relaxed) > 0 && SINK.with_borrow(Option::is_some)
+}
+
+pub fn emit(name: &str, fields: &[(&str, &dyn Display)]) {
+    SINK.with_borrow_mut(|sink| {
+        let Some(sink) = sink.as_mut().filter(|s| s.err.is_none()) else {
+            return;
+        };
+        let mut write = || {
+            write!(sink.out, "{name}")?;
+            for (key, value) in fields {
+                write!(sink.out, " {key}={value}")?;
+            }
+            writeln!(sink.out)
+        };
+        sink.err = write().err();
+    });
+}
+
+// Runs `f`, writing every event it emits on this thread to `out`.
+pub fn record<T>(out: impl Write + 'static, f: impl FnOnce() -> T) -> io::Result<T> {
+    // stops recording even if `f` panics
+    struct Stop;
+    impl Drop for Stop {
+        fn drop(&mut self) {
+            RECORDING.fetch_sub(1, Ordering::Relaxed);
+            SINK.take();
+        }
+    }
+
+    let sink = Sink {
+        out: BufWriter::new(Box::new(out)),
+        err: None,
+    };
+    assert!(SINK.replace(Some(sink)).is_none(), "already recording");
+    RECORDING.fetch_add(1, Ordering::Relaxed);
+    let stop = Stop;
+
+    let value = f();
+    let mut sink = SINK.take().unwrap();
+    drop(stop);
+
+    match sink.err.take() {
+        Some(e) => Err(e),
+        None => sink.out.flush().map(|_| value),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub name: String,
+    pub fields: Vec<(String, String)>,
+    // 1-based line in the trace it was read from
+    pub line: usize,
+}
+
+impl Event {
+    // any line is an event: words without an `=` become fields keyed by their position
+    pub fn parse(line: usize, text: &str) -> Self {
+        let mut words = text.split_whitespace();
+        let name = words.next().unwrap_or_default().to_owned();
+        let fields = words
+            .enumerate()
+            .map(|(idx, w)| match w.split_once('=') {
+                Some((k, v)) => (k.to_owned(), v.to_owned()),
+                None => ((idx + 1).to_string(), w.to_owned()),
+            })
+            .collect();
+        Self { name, fields, line }
+    }
+
+    fn same(&self, other: &Event) -> bool {
+        self.name == other.name && self.fields == other.fields
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (key, value) in &self.fields {
+            match key.parse::<usize>() {
+                Ok(_) => write!(f, " {value}")?,
+                Err(_) => write!(f, " {key}={value}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn parse(text: &str) -> Vec<Event> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(idx, l)| Event::parse(idx + 1, l))
+        .collect()
+}
+
+// where two traces stop agreeing
+pub struct Divergence {
+    // position among the aligned events
+    pub index: usize,
+    // the agreeing events just before it
+    pub before: Vec<Event>,
+    // what each trace has from there on, up to the context length; empty where it ended
+    pub left: Vec<Event>,
+    pub right: Vec<Event>,
+}
+
+// Lines the traces up on the event names both contain, so a trace from code with extra events
+// still compares against one without them, and finds the first event that differs.
+pub fn diverge(left: &[Event], right: &[Event], context: usize) -> Option<Divergence> {
+    let names =
+        |events: &[Event]| -> HashSet<String> { events.iter().map(|e| e.name.clone()).collect() };
+    let shared: HashSet<String> = names(left).intersection(&names(right)).cloned().collect();
+    let align = |events: &[Event]| -> Vec<Event> {
+        let kept = events.iter().filter(|e| shared.contains(&e.name));
+        kept.cloned().collect()
+    };
+    let (left, right) = (align(left), align(right));
+
+    let index = (0..left.len().max(right.len())).find(|&i| match (left.get(i), right.get(i)) {
+        (Some(l), Some(r)) => !l.same(r),
+        _ => true,
+    })?;
+
+    let tail = |events: &[Event]| {
+        events
+            .iter()
+            .skip(index)
+            .take(context.max(1))
+            .cloned()
+            .collect()
+    };
+    Some(Divergence {
+        index,
+        before: left[index.saturating_sub(context)..index].to_vec(),
+        left: tail(&left),
+        right: tail(&right),
+    })
+}
+
+impl Divergence {
+    // the fields that differ between the first diverging events, as `key: left != right`
+    pub fn changes(&self) -> Vec<String> {
+        let (Some(l), Some(r)) = (self.left.first(), self.right.first()) else {
+            return Vec::new();
+        };
+        if l.name != r.name {
+            return vec![format!("event: {} != {}", l.name, r.name)];
+        }
+
+        let mut keys: Vec<&String> = l.fields.iter().map(|(k, _)| k).collect();
+        for (k, _) in &r.fields {
+            if !keys.contains(&k) {
+                keys.push(k);
+            }
+        }
+        let value = |e: &Event, key: &String| {
+            let found = e.fields.iter().find(|(k, _)| k == key);
+            found.map_or("<none>".to_owned(), |(_, v)| v.clone())
+        };
+        keys.into_iter()
+            .filter(|k| value(l, k) != value(r, k))
+            .map(|k| match k.parse::<usize>() {
+                Ok(_) => format!("field {k}: {} != {}", value(l, k), value(r, k)),
+                Err(_) => format!("{k}: {} != {}", value(l, k), value(r, k)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    // a writer whose contents can be read back after `record` has consumed it
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records() {
+        event!("ignored", n = 1);
+        let out = Shared::default();
+        let value = record(out.clone(), || {
+            event!("step", n = 1, pos = format_args!("{},{}", 2, -3));
+            event!("done");
+            7
+        });
+
+        assert_eq!(7, value.unwrap());
+        assert!(!enabled());
+        let text = String::from_utf8(out.0.take()).unwrap();
+        assert_eq!("step n=1 pos=2,-3\ndone\n", text);
+    }
+
+    #[test]
+    fn diverges() {
+        let left = parse("turn monkey=0\ninspect old=79 new=500\ninspect old=98 new=620\n");
+        let right = parse("turn monkey=0\nextra\ninspect old=79 new=500\ninspect old=98 new=621\n");
+
+        let d = diverge(&left, &right, 5).unwrap();
+        assert_eq!(2, d.index);
+        assert_eq!(2, d.before.len());
+        assert_eq!(4, d.right[0].line);
+        assert_eq!(vec!["new: 620 != 621"], d.changes());
+
+        assert!(diverge(&left, &left, 5).is_none());
+        let d = diverge(&left, &left[..2], 5).unwrap();
+        assert!(d.right.is_empty());
+        assert_eq!(
+            "72 Mul(19) 456",
+            Event::parse(1, "72 Mul(19) 456").to_string()
+        );
+    }
+}
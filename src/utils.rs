@@ -1,67 +1,341 @@
-use std::fmt::Debug;
+use crate::error::{Error, Result};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{stdin, BufRead, BufReader, Read};
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-pub fn load_file<P>(p: P) -> BufReader<File>
+pub fn load_file<P>(p: P) -> Result<BufReader<File>>
 where
     P: AsRef<Path>,
 {
     let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     d.push(p);
-    let f = File::open(d).expect("failed to open file");
-    BufReader::new(f)
+    let f = File::open(d)?;
+    Ok(BufReader::new(f))
 }
 
-pub fn file_lines<P>(p: P) -> impl Iterator<Item = String>
-where
-    P: AsRef<Path>,
-{
-    load_file(p).lines().map(Result::unwrap)
+#[cfg(feature = "embedded-inputs")]
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/inputs.rs"));
+}
+
+// contents of a `src/decNN/*.txt` file or `answers.txt` compiled into the binary by the
+// `embedded-inputs` feature
+pub fn embedded<P: AsRef<Path>>(p: P) -> Option<&'static [u8]> {
+    #[cfg(feature = "embedded-inputs")]
+    {
+        let p = p.as_ref();
+        embedded::INPUTS
+            .iter()
+            .find(|(name, _)| Path::new(name) == p)
+            .map(|&(_, contents)| contents)
+    }
+
+    #[cfg(not(feature = "embedded-inputs"))]
+    {
+        let _ = p;
+        None
+    }
+}
+
+// paths of every embedded input, empty unless the `embedded-inputs` feature is on
+pub fn embedded_paths() -> impl Iterator<Item = &'static str> {
+    #[cfg(feature = "embedded-inputs")]
+    let inputs = embedded::INPUTS;
+    #[cfg(not(feature = "embedded-inputs"))]
+    let inputs: &[(&str, &[u8])] = &[];
+
+    inputs.iter().map(|&(name, _)| name)
 }
 
-pub fn must_parse<T>(v: &str) -> T
+// where a puzzle input comes from; parsers consume it without caring which
+pub enum Input<'a> {
+    // relative paths resolve against the crate root, absolute paths are used as-is; embedded
+    // inputs take precedence over the filesystem
+    Path(PathBuf),
+    Str(&'a str),
+    Bytes(&'a [u8]),
+    Reader(&'static str, Box<dyn BufRead + 'a>),
+}
+
+impl<'a> Input<'a> {
+    pub fn path<P: AsRef<Path>>(p: P) -> Self {
+        Self::Path(p.as_ref().to_path_buf())
+    }
+
+    pub fn reader<R: BufRead + 'a>(r: R) -> Self {
+        Self::Reader("<reader>", Box::new(r))
+    }
+
+    pub fn stdin() -> Self {
+        Self::Reader("<stdin>", Box::new(stdin().lock()))
+    }
+
+    // label used to prefix errors
+    pub fn name(&self) -> String {
+        match self {
+            Self::Path(p) => p.display().to_string(),
+            Self::Str(_) | Self::Bytes(_) => "<input>".to_owned(),
+            Self::Reader(name, _) => (*name).to_owned(),
+        }
+    }
+
+    pub fn open(self) -> Result<Box<dyn BufRead + 'a>> {
+        Ok(match self {
+            Self::Path(p) => match embedded(&p) {
+                Some(contents) => Box::new(contents),
+                None => Box::new(load_file(p)?),
+            },
+            Self::Str(s) => Box::new(s.as_bytes()),
+            Self::Bytes(b) => Box::new(b),
+            Self::Reader(_, r) => r,
+        })
+    }
+
+    // reads everything up front, so I/O errors surface here and parsers only deal with content
+    pub fn lines(self) -> Result<impl Iterator<Item = Line>> {
+        let lines = self
+            .open()?
+            .lines()
+            .enumerate()
+            .map(|(idx, l)| l.map(|text| Line::new(idx + 1, text)))
+            .collect::<Result<Vec<Line>, _>>()?;
+        Ok(lines.into_iter())
+    }
+
+    pub fn groups(self) -> Result<GroupIterator<impl Iterator<Item = Line>>> {
+        Ok(groups(self.lines()?))
+    }
+
+    pub fn bytes(self) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.open()?.read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    pub fn text(self) -> Result<String> {
+        let mut buf = String::new();
+        self.open()?.read_to_string(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl<'a> From<&'a str> for Input<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl<'a> From<&'a [u8]> for Input<'a> {
+    fn from(value: &'a [u8]) -> Self {
+        Self::Bytes(value)
+    }
+}
+
+pub fn parse_items<T>(i: impl Iterator<Item = Line>) -> impl Iterator<Item = Result<T>>
 where
     T: FromStr,
-    <T as FromStr>::Err: Debug,
+    <T as FromStr>::Err: Into<Error>,
 {
-    v.parse::<T>().unwrap()
+    i.map(|l| l.parse())
+}
+
+// splits lines into runs separated by blank lines
+pub fn groups<I: Iterator<Item = Line>>(inner: I) -> GroupIterator<I> {
+    GroupIterator { inner }
+}
+
+// 1-based column of `field`, which must be a subslice of `s`
+pub fn col_of(s: &str, field: &str) -> usize {
+    let offset = field.as_ptr() as usize - s.as_ptr() as usize;
+    debug_assert!(offset <= s.len(), "field is not part of the string");
+    offset + 1
 }
 
-pub fn parse_items<T>(i: impl Iterator<Item = String>) -> impl Iterator<Item = T>
+// parses `field`, a subslice of `s`, reporting failures at the field's column within `s`
+pub fn parse_field<T>(s: &str, field: &str) -> Result<T>
 where
     T: FromStr,
-    <T as FromStr>::Err: Debug,
+    <T as FromStr>::Err: Into<Error>,
 {
-    i.map(|s| must_parse(&s))
+    field
+        .parse::<T>()
+        .map_err(|e| e.into().offset(col_of(s, field) - 1))
 }
 
-pub fn file_groups<P>(p: P) -> impl Iterator<Item = impl Iterator<Item = String>>
-where
-    P: AsRef<Path>,
-{
-    let inner = file_lines(p);
-    GroupIterator { inner }
+// a single input line, tagged with its 1-based line number for error reporting
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub no: usize,
+    text: String,
+}
+
+impl Line {
+    pub fn new(no: usize, text: String) -> Self {
+        Self { no, text }
+    }
+
+    pub fn parse<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Into<Error>,
+    {
+        self.text
+            .parse::<T>()
+            .map_err(|e| e.into().at_line(self.no))
+    }
+
+    pub fn parse_field<T>(&self, field: &str) -> Result<T>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Into<Error>,
+    {
+        parse_field(&self.text, field).map_err(|e| e.at_line(self.no))
+    }
+
+    pub fn error(&self, col: usize, reason: impl Into<String>) -> Error {
+        Error::parse(col, reason).at_line(self.no)
+    }
+
+    pub fn field_error(&self, field: &str, reason: impl Into<String>) -> Error {
+        self.error(col_of(&self.text, field), reason)
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+impl Deref for Line {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+
+// constructs a value from consecutive lines of input, for types that span more than one line
+pub trait FromLines: Sized {
+    fn from_lines<I: Iterator<Item = Line>>(lines: I) -> Result<Self>;
 }
 
 pub struct GroupIterator<I>
 where
-    I: Iterator<Item = String>,
+    I: Iterator<Item = Line>,
 {
     inner: I,
 }
 
-impl<I: Iterator<Item = String>> Iterator for GroupIterator<I> {
-    type Item = impl Iterator<Item = String>;
+impl<I: Iterator<Item = Line>> Iterator for GroupIterator<I> {
+    type Item = Group;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let group: Vec<String> = (&mut self.inner).take_while(|l| !l.is_empty()).collect();
+        let group: Vec<Line> = (&mut self.inner).take_while(|l| !l.is_empty()).collect();
 
         if group.is_empty() {
             return None;
         }
 
-        Some(group.into_iter())
+        Some(Group(group.into_iter()))
+    }
+}
+
+// the lines of a single blank-line-delimited group
+pub struct Group(std::vec::IntoIter<Line>);
+
+impl Iterator for Group {
+    type Item = Line;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+// Stable stand-ins for the unstable `Iterator::array_chunks` and `Iterator::next_chunk`. With the
+// `nightly` feature they defer to std instead.
+pub trait IterChunks: Iterator + Sized {
+    fn arrays<const N: usize>(self) -> ArrayChunks<Self, N>;
+
+    fn next_array<const N: usize>(&mut self) -> Option<[Self::Item; N]>;
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<I: Iterator> IterChunks for I {
+    fn arrays<const N: usize>(self) -> ArrayChunks<Self, N> {
+        ArrayChunks { iter: self }
+    }
+
+    fn next_array<const N: usize>(&mut self) -> Option<[Self::Item; N]> {
+        let chunk: Vec<Self::Item> = self.by_ref().take(N).collect();
+        chunk.try_into().ok()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<I: Iterator> IterChunks for I {
+    fn arrays<const N: usize>(self) -> ArrayChunks<Self, N> {
+        Iterator::array_chunks(self)
+    }
+
+    fn next_array<const N: usize>(&mut self) -> Option<[Self::Item; N]> {
+        Iterator::next_chunk(self).ok()
+    }
+}
+
+#[cfg(feature = "nightly")]
+pub use std::iter::ArrayChunks;
+
+// yields non-overlapping arrays of `N` items, dropping any incomplete remainder
+#[cfg(not(feature = "nightly"))]
+pub struct ArrayChunks<I: Iterator, const N: usize> {
+    iter: I,
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<I: Iterator, const N: usize> Iterator for ArrayChunks<I, N> {
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_array()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_split_on_blank_lines() {
+        let lines = Input::Str("a\nb\n\nc\n").lines().unwrap();
+        let got: Vec<Vec<String>> = groups(lines)
+            .map(|g| g.map(Line::into_string).collect())
+            .collect();
+        assert_eq!(vec![vec!["a", "b"], vec!["c"]], got);
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-inputs")]
+    fn embedded_inputs() {
+        assert!(embedded_paths().any(|p| p == "src/dec01/examples/1.txt"));
+
+        let on_disk = std::fs::read("src/dec01/examples/1.txt").unwrap();
+        let input = Input::path("src/dec01/examples/1.txt").bytes().unwrap();
+        assert_eq!(on_disk, input);
+        assert_eq!(None, embedded("src/dec01/missing.txt"));
+    }
+
+    #[test]
+    fn chunks() {
+        let got: Vec<[u8; 2]> = [1, 2, 3, 4, 5].into_iter().arrays().collect();
+        assert_eq!(vec![[1, 2], [3, 4]], got);
+
+        let mut iter = [1, 2, 3].into_iter();
+        assert_eq!(Some([1, 2]), iter.next_array());
+        assert_eq!(None, iter.next_array::<2>());
     }
 }
@@ -0,0 +1,61 @@
+use aoc_2022::inputs;
+use aoc_2022::manifest::Manifest;
+use aoc_2022::{find_day, Input, DAYS};
+
+// Checks every answer in answers.txt, collecting all mismatches before failing.
+#[test]
+fn answers() {
+    let manifest = Manifest::load().unwrap_or_else(|e| panic!("{e}"));
+    let mut failures = Vec::new();
+
+    for entry in manifest.entries() {
+        let label = format!("{} part {} {}", entry.day, entry.part, entry.input);
+
+        let Some(day) = find_day(&entry.day) else {
+            failures.push(format!("{label}: unknown day"));
+            continue;
+        };
+
+        let answer = match day.run(Input::path(entry.input_path()), entry.part) {
+            Ok(answer) => answer,
+            Err(e) => {
+                failures.push(format!("{label}: {e}"));
+                continue;
+            }
+        };
+
+        if answer != entry.answer {
+            if answer.contains('\n') || entry.answer.contains('\n') {
+                failures.push(format!(
+                    "{label}: expected\n{}\ngot\n{answer}",
+                    entry.answer
+                ));
+            } else {
+                failures.push(format!("{label}: expected {}, got {answer}", entry.answer));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} answers wrong:\n{}",
+        failures.len(),
+        manifest.entries().len(),
+        failures.join("\n")
+    );
+}
+
+// Every input on disk should at least parse, answered in the manifest or not.
+#[test]
+fn inputs_parse() {
+    let mut failures = Vec::new();
+    for day in DAYS {
+        for file in inputs::list(day.name).unwrap_or_else(|e| panic!("{e}")) {
+            if let Err(e) = day.parse(file.input()) {
+                failures.push(format!("{} {}: {e}", day.name, file.name));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,27 @@
+use aoc_2022::dec07::{Dec07, FS};
+use aoc_2022::dec12::{Dec12, Idx};
+use aoc_2022::dec13::{Dec13, PacketDatum, Pair};
+use aoc_2022::{FromLines, Input, Solution};
+
+// Exercises the domain types from outside the crate, the way a dependent tool would.
+#[test]
+fn domain_types() {
+    let fs: FS = Dec07::parse(Input::path("src/dec07/examples/1.txt")).unwrap();
+    assert_eq!(Some(48381165), fs.dir_size("/"));
+    assert_eq!(Some(584), fs.file_size("/a/e/i"));
+    assert_eq!(95437, fs.sum_of_dirs_at_most(100_000));
+
+    let mountain = Dec12::parse(Input::path("src/dec12/examples/1.txt")).unwrap();
+    assert_eq!(Idx(0, 0), mountain.start());
+    assert_eq!('z' as u32, mountain[mountain.end()].elevation());
+    assert_eq!(31, mountain.shortest_path(mountain.start()).unwrap());
+
+    let lines = Input::Str("[1,[3]]\n[1,2]\n").lines().unwrap();
+    let pair = Pair::from_lines(lines).unwrap();
+    let inner = PacketDatum::List(vec![PacketDatum::Int(3)]);
+    assert_eq!(PacketDatum::List(vec![PacketDatum::Int(1), inner]), pair.0);
+    assert!(!pair.in_order());
+
+    let pairs = Dec13::parse(Input::path("src/dec13/examples/1.txt")).unwrap();
+    assert_eq!(13, Dec13::part_1(&pairs));
+}
@@ -0,0 +1,41 @@
+use aoc_2022::{fuzz, inputs, DAYS};
+use std::fs;
+
+// Every crash the fuzzer has saved must now parse without panicking.
+#[test]
+fn crashes_fixed() {
+    let mut failures = Vec::new();
+    for day in DAYS {
+        for path in fuzz::crash_files(day.name).unwrap_or_else(|e| panic!("{e}")) {
+            let data = fs::read(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            if let Err(message) = fuzz::try_parse(day, &data) {
+                failures.push(format!("{}: {message}", path.display()));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+// A short run with a fixed seed, so new parser panics show up without running `aoc fuzz`.
+#[test]
+fn smoke() {
+    let mut failures = Vec::new();
+
+    for day in DAYS {
+        let mut seeds = Vec::new();
+        for file in inputs::list(day.name).unwrap_or_else(|e| panic!("{e}")) {
+            if file.is_example() {
+                seeds.push(file.input().bytes().unwrap_or_else(|e| panic!("{e}")));
+            }
+        }
+
+        let report = fuzz::fuzz(day, &seeds, 300, 1);
+        for crash in report.crashes {
+            let input = String::from_utf8_lossy(&crash.input);
+            failures.push(format!("{}: {}\n{input}", day.name, crash.message));
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}